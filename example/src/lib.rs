@@ -1,11 +1,4 @@
-mod opt;
-
-use opt::*;
-
-use argmin::prelude::*;
-use argmin::solver::gradientdescent::SteepestDescent;
-use argmin::solver::linesearch::MoreThuenteLineSearch;
-use serde::Deserialize;
+use serde::Serialize;
 
 use cfg_if::cfg_if;
 use wasm_bindgen::prelude::*;
@@ -33,12 +26,10 @@ cfg_if! {
     }
 }
 
-#[derive(Deserialize)]
-pub struct OptParam {
-    pub eps: f64,
-    pub samples: u64,
-    pub max_iters: u64,
-    pub target_cost: f64,
+#[derive(Serialize)]
+pub struct OptResult {
+    pub best_param: Vec<f64>,
+    pub report: OptimizeReport,
 }
 
 #[wasm_bindgen]
@@ -76,17 +67,45 @@ pub fn wps_to_jaci_path(wps_value: &JsValue, param_value: &JsValue) -> JsValue {
     JsValue::from_serde(&segments).unwrap()
 }
 
+#[wasm_bindgen]
+pub fn wps_to_cubic_jaci_path(wps_value: &JsValue, param_value: &JsValue) -> JsValue {
+    let param: Jaci = param_value.into_serde().unwrap();
+    let wps: Vec<Waypoint> = wps_value.into_serde().unwrap();
+
+    let cubics = cubic_hermites(&wps);
+
+    let segments = param.parameterize(&cubics);
+
+    JsValue::from_serde(&segments).unwrap()
+}
+
+#[wasm_bindgen]
+pub fn points_to_wps(points_value: &JsValue, tcb_value: &JsValue) -> JsValue {
+    let points: Vec<ControlPoint> = points_value.into_serde().unwrap();
+    let tcb: KochanekBartels = tcb_value.into_serde().unwrap();
+
+    let wps = WaypointBuilder::new()
+        .kochanek_bartels(tcb.tension, tcb.continuity, tcb.bias)
+        .points(&points)
+        .build()
+        .unwrap();
+
+    JsValue::from_serde(&wps).unwrap()
+}
+
 #[wasm_bindgen]
 pub fn optimize(wps_value: &JsValue, param_value: &JsValue) -> JsValue {
-    let param: OptParam = param_value.into_serde().unwrap();
+    let param: OptimizeOptions = param_value.into_serde().unwrap();
 
-    let wps: Vec<Waypoint> = wps_value.into_serde().unwrap();
+    let mut wps: Vec<Waypoint> = wps_value.into_serde().unwrap();
 
     if wps.len() <= 2 {
         return JsValue::from_serde(&Vec::<f64>::new()).unwrap();
     }
 
-    let init_acc = wps
+    let report = optimize_curvature(&mut wps, &param);
+
+    let best_param = wps
         .iter()
         .enumerate()
         .filter(|(j, _)| *j > 0 && *j < wps.len() - 1)
@@ -94,23 +113,7 @@ pub fn optimize(wps_value: &JsValue, param_value: &JsValue) -> JsValue {
         .flatten()
         .collect();
 
-    let linesearch = MoreThuenteLineSearch::new();
-
-    let solver = SteepestDescent::new(linesearch);
-
-    let cost = OptProblem {
-        waypoints: wps,
-        eps: param.eps,
-        samples: param.samples,
-    };
-
-    let res = Executor::new(cost, solver, init_acc)
-        .max_iters(param.max_iters)
-        .target_cost(param.target_cost)
-        .run()
-        .unwrap();
-
-    JsValue::from_serde(&res.state).unwrap()
+    JsValue::from_serde(&OptResult { best_param, report }).unwrap()
 }
 
 #[cfg(test)]
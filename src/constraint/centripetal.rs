@@ -0,0 +1,22 @@
+use crate::{Point, TrajectoryConstraint};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CentripetalAccelerationConstraint {
+    max_centripetal_acceleration: f64,
+}
+
+impl CentripetalAccelerationConstraint {
+    pub fn new(max_centripetal_acceleration: f64) -> Self {
+        CentripetalAccelerationConstraint {
+            max_centripetal_acceleration,
+        }
+    }
+}
+
+impl TrajectoryConstraint for CentripetalAccelerationConstraint {
+    fn max_velocity(&self, point: &Point) -> f64 {
+        // a_c = v^2 * k
+        (self.max_centripetal_acceleration / point.curvature().abs()).sqrt()
+    }
+}
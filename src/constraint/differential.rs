@@ -0,0 +1,28 @@
+use crate::{DifferentialDriveKinematics, Point, TrajectoryConstraint};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DifferentialDriveConstraint {
+    track_width: f64,
+    max_wheel_speed: f64,
+}
+
+impl DifferentialDriveConstraint {
+    pub fn new(track_width: f64, max_wheel_speed: f64) -> Self {
+        DifferentialDriveConstraint {
+            track_width,
+            max_wheel_speed,
+        }
+    }
+
+    pub fn from_kinematics(kinematics: &DifferentialDriveKinematics, max_wheel_speed: f64) -> Self {
+        DifferentialDriveConstraint::new(kinematics.effective_track_width(), max_wheel_speed)
+    }
+}
+
+impl TrajectoryConstraint for DifferentialDriveConstraint {
+    fn max_velocity(&self, point: &Point) -> f64 {
+        // the outside wheel travels at v * (1 + |k| * w / 2)
+        self.max_wheel_speed / (1. + point.curvature().abs() * self.track_width / 2.)
+    }
+}
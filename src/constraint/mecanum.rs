@@ -0,0 +1,33 @@
+use crate::{ChassisSpeeds, MecanumDriveKinematics, Point, TrajectoryConstraint};
+use serde::{Deserialize, Serialize};
+
+// Points carry no holonomic rotation, so the limit assumes the robot faces
+// along the path and turns with it. A robot holding a separate heading
+// loads its wheels differently; driving diagonally needs up to sqrt(2) times
+// the wheel speed, so leave that headroom in max_wheel_speed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MecanumDriveConstraint {
+    kinematics: MecanumDriveKinematics,
+    max_wheel_speed: f64,
+}
+
+impl MecanumDriveConstraint {
+    pub fn new(kinematics: MecanumDriveKinematics, max_wheel_speed: f64) -> Self {
+        MecanumDriveConstraint {
+            kinematics,
+            max_wheel_speed,
+        }
+    }
+}
+
+impl TrajectoryConstraint for MecanumDriveConstraint {
+    fn max_velocity(&self, point: &Point) -> f64 {
+        // wheel speeds are linear in v with the heading rate v * k, so
+        // evaluate them at unit path velocity and scale
+        let wheels =
+            self.kinematics
+                .to_wheel_speeds(&ChassisSpeeds::new(1., 0., point.curvature()));
+
+        self.max_wheel_speed / wheels.fastest()
+    }
+}
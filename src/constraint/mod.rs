@@ -0,0 +1,134 @@
+mod centripetal;
+mod differential;
+mod mecanum;
+mod region;
+
+pub use centripetal::*;
+pub use differential::*;
+pub use mecanum::*;
+pub use region::*;
+
+use crate::Point;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AccelerationLimits {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl AccelerationLimits {
+    pub fn new(min: f64, max: f64) -> Self {
+        AccelerationLimits { min, max }
+    }
+
+    pub fn unbounded() -> Self {
+        AccelerationLimits::new(f64::NEG_INFINITY, f64::INFINITY)
+    }
+
+    pub fn intersect(&self, other: &AccelerationLimits) -> Self {
+        AccelerationLimits::new(self.min.max(other.min), self.max.min(other.max))
+    }
+}
+
+pub trait TrajectoryConstraint {
+    fn max_velocity(&self, point: &Point) -> f64;
+
+    fn acceleration_limits(&self, _: &Point, _: f64) -> AccelerationLimits {
+        AccelerationLimits::unbounded()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hermites, time_parameterize, ChassisSpeeds, Jaci, MecanumDriveKinematics, Parameterizer,
+        State, Trajectory, TrajectoryConfig, Waypoint,
+    };
+
+    const EPS: f64 = 1e-9;
+
+    fn trajectory(constraints: &[&dyn TrajectoryConstraint]) -> Trajectory {
+        let wps = [
+            Waypoint::new(0., 0., 1., 0., 0., 0.),
+            Waypoint::new(2., 2., 0., 1., 0., 0.),
+            Waypoint::new(0., 4., -1., 0., 0., 0.),
+        ];
+        let points = Jaci::new(0.05, 0.01).parameterize(&hermites(&wps));
+        time_parameterize(&points, &TrajectoryConfig::new(3., 2., 0., 0.), constraints)
+    }
+
+    #[test]
+    fn intersect_keeps_the_tighter_bounds() {
+        let limits = AccelerationLimits::new(-2., 1.).intersect(&AccelerationLimits::new(-1., 3.));
+        assert_eq!((limits.min, limits.max), (-1., 1.));
+
+        let limits = AccelerationLimits::unbounded().intersect(&AccelerationLimits::new(-1., 3.));
+        assert_eq!((limits.min, limits.max), (-1., 3.));
+    }
+
+    #[test]
+    fn centripetal_acceleration_stays_under_the_limit() {
+        let trajectory = trajectory(&[&CentripetalAccelerationConstraint::new(0.5)]);
+        let centripetal: Vec<f64> = trajectory
+            .states()
+            .iter()
+            .map(|state| state.velocity.powi(2) * state.curvature.abs())
+            .collect();
+
+        assert!(centripetal.iter().all(|a| *a <= 0.5 + EPS));
+        assert!(centripetal.iter().any(|a| *a > 0.45));
+    }
+
+    #[test]
+    fn differential_drive_outer_wheel_stays_under_the_limit() {
+        let trajectory = trajectory(&[&DifferentialDriveConstraint::new(0.6, 1.5)]);
+        let outer: Vec<f64> = trajectory
+            .states()
+            .iter()
+            .map(|state| state.velocity.abs() * (1. + state.curvature.abs() * 0.3))
+            .collect();
+
+        assert!(outer.iter().all(|v| *v <= 1.5 + EPS));
+        assert!(outer.iter().any(|v| *v > 1.4));
+    }
+
+    #[test]
+    fn mecanum_fastest_wheel_stays_under_the_limit() {
+        let kinematics =
+            MecanumDriveKinematics::new([0.3, 0.25], [0.3, -0.25], [-0.3, 0.25], [-0.3, -0.25]);
+        let trajectory = trajectory(&[&MecanumDriveConstraint::new(kinematics, 1.5)]);
+        let fastest: Vec<f64> = trajectory
+            .states()
+            .iter()
+            .map(|state| {
+                let speeds =
+                    ChassisSpeeds::new(state.velocity, 0., state.velocity * state.curvature);
+                kinematics.to_wheel_speeds(&speeds).fastest()
+            })
+            .collect();
+
+        assert!(fastest.iter().all(|v| *v <= 1.5 + EPS));
+        assert!(fastest.iter().any(|v| *v > 1.4));
+    }
+
+    #[test]
+    fn region_limits_velocity_only_inside() {
+        let region = Region::Circle {
+            center: [2., 2.],
+            radius: 0.5,
+        };
+        let trajectory = trajectory(&[&RegionConstraint::new(region, 0.25)]);
+        let (inside, outside): (Vec<&State>, Vec<&State>) = trajectory
+            .states()
+            .iter()
+            .partition(|state| region.contains(state.pose.translation()));
+
+        assert!(!inside.is_empty());
+        assert!(inside
+            .iter()
+            .all(|state| state.velocity.abs() <= 0.25 + EPS));
+        assert!(outside.iter().any(|state| state.velocity.abs() > 1.));
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{Coordinate, Point, TrajectoryConstraint, Vec2, Vector};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Region {
+    Rectangle { corner: Vec2, opposite: Vec2 },
+    Circle { center: Vec2, radius: f64 },
+}
+
+impl Region {
+    pub fn contains(&self, position: Vec2) -> bool {
+        match self {
+            Region::Rectangle { corner, opposite } => {
+                position.x() >= corner.x().min(opposite.x())
+                    && position.x() <= corner.x().max(opposite.x())
+                    && position.y() >= corner.y().min(opposite.y())
+                    && position.y() <= corner.y().max(opposite.y())
+            }
+            Region::Circle { center, radius } => {
+                position.add(center.scale(-1.)).norm_squared() <= radius * radius
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RegionConstraint {
+    region: Region,
+    max_velocity: f64,
+}
+
+impl RegionConstraint {
+    pub fn new(region: Region, max_velocity: f64) -> Self {
+        RegionConstraint {
+            region,
+            max_velocity,
+        }
+    }
+}
+
+impl TrajectoryConstraint for RegionConstraint {
+    fn max_velocity(&self, point: &Point) -> f64 {
+        if self.region.contains(point.position) {
+            self.max_velocity
+        } else {
+            f64::INFINITY
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WayfinderError {
+    EmptyInput,
+    NotEnoughWaypoints { count: usize },
+    NonFiniteWaypoint { index: usize },
+    ZeroTangent { index: usize },
+    CoincidentWaypoints { index: usize },
+    NonFinitePoint { segment: usize },
+    StationaryPoint { segment: usize },
+    InfeasibleProfile { index: usize },
+    UndefinedHeading { index: usize },
+    NotEnoughControlPoints { degree: usize, count: usize },
+    InvalidKnots,
+}
+
+impl fmt::Display for WayfinderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WayfinderError::EmptyInput => write!(f, "no splines were given to parameterize"),
+            WayfinderError::NotEnoughWaypoints { count } => write!(
+                f,
+                "a path needs at least two waypoints, but {} were given",
+                count
+            ),
+            WayfinderError::NonFiniteWaypoint { index } => {
+                write!(f, "waypoint {} contains a NaN or infinite value", index)
+            }
+            WayfinderError::ZeroTangent { index } => {
+                write!(f, "waypoint {} has a zero-length tangent", index)
+            }
+            WayfinderError::CoincidentWaypoints { index } => write!(
+                f,
+                "waypoints {} and {} are at the same position",
+                index,
+                index + 1
+            ),
+            WayfinderError::NonFinitePoint { segment } => {
+                write!(f, "segment {} produced a NaN or infinite point", segment)
+            }
+            WayfinderError::StationaryPoint { segment } => write!(
+                f,
+                "segment {} comes to a stop, so its heading is undefined",
+                segment
+            ),
+            WayfinderError::InfeasibleProfile { index } => write!(
+                f,
+                "the robot is at rest on both sides of point {}, so it can never move past it",
+                index
+            ),
+            WayfinderError::UndefinedHeading { index } => write!(
+                f,
+                "point {} is stationary, so its heading is undefined",
+                index
+            ),
+            WayfinderError::NotEnoughControlPoints { degree, count } => write!(
+                f,
+                "a degree {} spline needs more than {} control points, but {} were given",
+                degree, degree, count
+            ),
+            WayfinderError::InvalidKnots => write!(
+                f,
+                "knot vector must be non-decreasing with one more knot than control points plus degree"
+            ),
+        }
+    }
+}
+
+impl Error for WayfinderError {}
@@ -0,0 +1,185 @@
+use crate::{ChassisSpeeds, Rotation, Trajectory, Translation, Twist, WheelState};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DifferentialDriveWheelSpeeds {
+    pub left: f64,
+    pub right: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DifferentialDriveWheelStates {
+    pub time: f64,
+    pub left: WheelState,
+    pub right: WheelState,
+}
+
+// The scrub factor scales the track width to account for wheels slipping
+// sideways while turning; 1.0 is an ideal drivetrain.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DifferentialDriveKinematics {
+    track_width: f64,
+    scrub_factor: f64,
+}
+
+impl DifferentialDriveKinematics {
+    pub fn new(track_width: f64) -> Self {
+        DifferentialDriveKinematics {
+            track_width,
+            scrub_factor: 1.,
+        }
+    }
+
+    pub fn with_scrub_factor(self, scrub_factor: f64) -> Self {
+        DifferentialDriveKinematics {
+            scrub_factor,
+            ..self
+        }
+    }
+
+    pub fn effective_track_width(&self) -> f64 {
+        self.track_width * self.scrub_factor
+    }
+
+    pub fn to_wheel_speeds(&self, speeds: &ChassisSpeeds) -> DifferentialDriveWheelSpeeds {
+        let turn = speeds.omega * self.effective_track_width() / 2.;
+
+        DifferentialDriveWheelSpeeds {
+            left: speeds.vx - turn,
+            right: speeds.vx + turn,
+        }
+    }
+
+    pub fn to_chassis_speeds(&self, wheels: &DifferentialDriveWheelSpeeds) -> ChassisSpeeds {
+        ChassisSpeeds::new(
+            (wheels.left + wheels.right) / 2.,
+            0.,
+            (wheels.right - wheels.left) / self.effective_track_width(),
+        )
+    }
+
+    // odometry update from the distance each wheel travelled
+    pub fn to_twist(&self, left_distance: f64, right_distance: f64) -> Twist {
+        Twist::new(
+            (left_distance + right_distance) / 2.,
+            0.,
+            (right_distance - left_distance) / self.effective_track_width(),
+        )
+    }
+
+    // Wheel positions follow from the distance and heading change between
+    // states, which is exact along arcs; accelerations are differenced
+    // forwards in time like the trajectory's own.
+    pub fn wheel_states(&self, trajectory: &Trajectory) -> Vec<DifferentialDriveWheelStates> {
+        let states = trajectory.states();
+        let half_width = self.effective_track_width() / 2.;
+
+        let speeds: Vec<DifferentialDriveWheelSpeeds> = states
+            .iter()
+            .map(|state| self.to_wheel_speeds(&ChassisSpeeds::from_state(state)))
+            .collect();
+
+        let mut out = Vec::with_capacity(states.len());
+        let (mut left, mut right) = (0., 0.);
+
+        for (i, state) in states.iter().enumerate() {
+            let (left_acceleration, right_acceleration) = match states.get(i + 1) {
+                Some(next) if next.time - state.time > 1e-9 => {
+                    let dt = next.time - state.time;
+                    (
+                        (speeds[i + 1].left - speeds[i].left) / dt,
+                        (speeds[i + 1].right - speeds[i].right) / dt,
+                    )
+                }
+                _ => (0., 0.),
+            };
+
+            out.push(DifferentialDriveWheelStates {
+                time: state.time,
+                left: WheelState {
+                    position: left,
+                    velocity: speeds[i].left,
+                    acceleration: left_acceleration,
+                },
+                right: WheelState {
+                    position: right,
+                    velocity: speeds[i].right,
+                    acceleration: right_acceleration,
+                },
+            });
+
+            if let Some(next) = states.get(i + 1) {
+                // distance only grows, so take the direction from the speeds
+                let direction = if state.velocity < 0. || next.velocity < 0. {
+                    -1.
+                } else {
+                    1.
+                };
+                let ds = direction * (next.distance - state.distance);
+                let turn =
+                    Translation::rotate_by(next.pose.rotation(), state.pose.rotation().inverse())
+                        .as_radians()
+                        * half_width;
+                left += ds - turn;
+                right += ds + turn;
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{time_parameterize, Clothoid, Directed, Spline, TrajectoryConfig};
+    use std::f64::consts::PI;
+
+    const EPS: f64 = 1e-6;
+
+    fn trajectory(segment: Directed<Clothoid>) -> Trajectory {
+        let points: Vec<_> = (0..=100)
+            .map(|i| segment.point_at(i as f64 / 100.))
+            .collect();
+        time_parameterize(&points, &TrajectoryConfig::new(1., 1., 0., 0.), &[])
+    }
+
+    #[test]
+    fn straight_line_drives_both_wheels_its_length() {
+        let line = Clothoid::new([0., 0.], 0., 0., 0., 4.);
+        let states = DifferentialDriveKinematics::new(0.6)
+            .wheel_states(&trajectory(Directed::forward(line)));
+        let last = states.last().unwrap();
+
+        assert!((last.left.position - 4.).abs() < EPS);
+        assert!((last.right.position - 4.).abs() < EPS);
+        for state in &states {
+            assert!((state.left.velocity - state.right.velocity).abs() < EPS);
+        }
+    }
+
+    #[test]
+    fn arc_splits_the_wheels_by_the_track_width() {
+        // a quarter circle of radius 2 to the left
+        let arc = Clothoid::new([0., 0.], 0., 0.5, 0., PI);
+        let states =
+            DifferentialDriveKinematics::new(0.6).wheel_states(&trajectory(Directed::forward(arc)));
+        let last = states.last().unwrap();
+
+        assert!((last.left.position - 1.7 * PI / 2.).abs() < 1e-3);
+        assert!((last.right.position - 2.3 * PI / 2.).abs() < 1e-3);
+        assert!((last.right.position - last.left.position - 0.6 * PI / 2.).abs() < EPS);
+    }
+
+    #[test]
+    fn reversed_path_runs_the_wheels_backwards() {
+        let line = Clothoid::new([0., 0.], 0., 0., 0., 3.);
+        let states = DifferentialDriveKinematics::new(0.6)
+            .wheel_states(&trajectory(Directed::backward(line)));
+        let last = states.last().unwrap();
+
+        assert!((last.left.position + 3.).abs() < EPS);
+        assert!((last.right.position + 3.).abs() < EPS);
+        assert!(states.iter().all(|state| state.left.velocity <= 0.));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{ChassisSpeeds, Coordinate, Mat3, MatMul, Matrix, Vec2, Vec3, Vector};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MecanumDriveWheelSpeeds {
+    pub front_left: f64,
+    pub front_right: f64,
+    pub rear_left: f64,
+    pub rear_right: f64,
+}
+
+impl MecanumDriveWheelSpeeds {
+    pub fn new(front_left: f64, front_right: f64, rear_left: f64, rear_right: f64) -> Self {
+        MecanumDriveWheelSpeeds {
+            front_left,
+            front_right,
+            rear_left,
+            rear_right,
+        }
+    }
+
+    fn as_array(&self) -> [f64; 4] {
+        [
+            self.front_left,
+            self.front_right,
+            self.rear_left,
+            self.rear_right,
+        ]
+    }
+
+    pub fn fastest(&self) -> f64 {
+        self.as_array()
+            .iter()
+            .map(|speed| speed.abs())
+            .fold(0., f64::max)
+    }
+
+    // scales every wheel down together so the fastest runs at max_speed
+    pub fn desaturate(&self, max_speed: f64) -> Self {
+        let fastest = self.fastest();
+
+        if fastest > max_speed {
+            let [fl, fr, rl, rr] = self.as_array().map(|speed| speed * max_speed / fastest);
+            MecanumDriveWheelSpeeds::new(fl, fr, rl, rr)
+        } else {
+            *self
+        }
+    }
+}
+
+// Wheel positions are robot-relative, x forward and y to the left, with
+// rollers at 45 degrees forming an X when viewed from above.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MecanumDriveKinematics {
+    front_left: Vec2,
+    front_right: Vec2,
+    rear_left: Vec2,
+    rear_right: Vec2,
+}
+
+impl MecanumDriveKinematics {
+    pub fn new(front_left: Vec2, front_right: Vec2, rear_left: Vec2, rear_right: Vec2) -> Self {
+        MecanumDriveKinematics {
+            front_left,
+            front_right,
+            rear_left,
+            rear_right,
+        }
+    }
+
+    // rows of the inverse kinematics matrix, mapping [vx, vy, omega] to
+    // front left, front right, rear left and rear right wheel speeds
+    fn inverse_rows(&self) -> [Vec3; 4] {
+        [
+            [1., -1., -(self.front_left.x() + self.front_left.y())],
+            [1., 1., self.front_right.x() - self.front_right.y()],
+            [1., 1., self.rear_left.x() - self.rear_left.y()],
+            [1., -1., -(self.rear_right.x() + self.rear_right.y())],
+        ]
+    }
+
+    pub fn to_wheel_speeds(&self, speeds: &ChassisSpeeds) -> MecanumDriveWheelSpeeds {
+        let chassis = [speeds.vx, speeds.vy, speeds.omega];
+        let [fl, fr, rl, rr] = self.inverse_rows().map(|row| row.dot(&chassis));
+        MecanumDriveWheelSpeeds::new(fl, fr, rl, rr)
+    }
+
+    // least-squares solution through the normal equations
+    pub fn to_chassis_speeds(&self, wheels: &MecanumDriveWheelSpeeds) -> Option<ChassisSpeeds> {
+        let rows = self.inverse_rows();
+        let mut normal: Mat3 = [[0.; 3]; 3];
+        let mut rhs: Vec3 = [0.; 3];
+
+        for (row, speed) in rows.iter().zip(&wheels.as_array()) {
+            for i in 0..3 {
+                normal[i] = normal[i].add(row.scale(row[i]));
+            }
+            rhs = rhs.add(row.scale(*speed));
+        }
+
+        let [vx, vy, omega] = normal.inverse()?.mul(&rhs);
+        Some(ChassisSpeeds::new(vx, vy, omega))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPS: f64 = 1e-9;
+
+    fn kinematics() -> MecanumDriveKinematics {
+        MecanumDriveKinematics::new([0.3, 0.25], [0.3, -0.25], [-0.3, 0.25], [-0.3, -0.25])
+    }
+
+    #[test]
+    fn round_trips_chassis_speeds() {
+        let kinematics = kinematics();
+
+        for &(vx, vy, omega) in &[(1., 0., 0.), (0., 1., 0.), (0., 0., 1.), (1., 0.5, 0.3)] {
+            let wheels = kinematics.to_wheel_speeds(&ChassisSpeeds::new(vx, vy, omega));
+            let speeds = kinematics.to_chassis_speeds(&wheels).unwrap();
+
+            assert!((speeds.vx - vx).abs() < EPS);
+            assert!((speeds.vy - vy).abs() < EPS);
+            assert!((speeds.omega - omega).abs() < EPS);
+        }
+    }
+
+    #[test]
+    fn strafing_runs_the_diagonals_against_each_other() {
+        let wheels = kinematics().to_wheel_speeds(&ChassisSpeeds::new(0., 1., 0.));
+
+        assert_eq!(wheels.as_array(), [-1., 1., 1., -1.]);
+    }
+
+    #[test]
+    fn desaturate_caps_the_fastest_wheel_and_keeps_ratios() {
+        let wheels = MecanumDriveWheelSpeeds::new(4., -2., 1., -0.5);
+        let desaturated = wheels.desaturate(2.);
+
+        assert!((desaturated.fastest() - 2.).abs() < EPS);
+        for (after, before) in desaturated.as_array().iter().zip(&wheels.as_array()) {
+            assert!((after - before / 2.).abs() < EPS);
+        }
+    }
+
+    #[test]
+    fn desaturate_leaves_slow_wheels_alone() {
+        let wheels = MecanumDriveWheelSpeeds::new(1., -0.5, 0.2, 0.);
+
+        assert_eq!(wheels.desaturate(2.).as_array(), wheels.as_array());
+    }
+}
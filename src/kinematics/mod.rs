@@ -0,0 +1,39 @@
+mod differential;
+mod mecanum;
+mod swerve;
+
+pub use differential::*;
+pub use mecanum::*;
+pub use swerve::*;
+
+use crate::{Rotation, State, Translation, Vector};
+use serde::{Deserialize, Serialize};
+
+// Robot-relative velocity: vx forward, vy to the left, omega counterclockwise.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ChassisSpeeds {
+    pub vx: f64,
+    pub vy: f64,
+    pub omega: f64,
+}
+
+impl ChassisSpeeds {
+    pub fn new(vx: f64, vy: f64, omega: f64) -> Self {
+        ChassisSpeeds { vx, vy, omega }
+    }
+
+    // robot-relative speeds, so travel is rotated into the robot's facing
+    pub fn from_state(state: &State) -> Self {
+        let [vx, vy] =
+            Translation::rotate_by(state.pose.rotation(), state.holonomic_rotation.inverse())
+                .scale(state.velocity);
+        ChassisSpeeds::new(vx, vy, state.angular_velocity)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WheelState {
+    pub position: f64,
+    pub velocity: f64,
+    pub acceleration: f64,
+}
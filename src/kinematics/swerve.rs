@@ -0,0 +1,232 @@
+use crate::{
+    ChassisSpeeds, Coordinate, Mat3, MatMul, Matrix, Rotation, Trajectory, Translation, Vec2, Vec3,
+    Vector,
+};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::FRAC_PI_2;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SwerveModuleState {
+    pub speed: f64,
+    pub angle: Vec2,
+}
+
+impl SwerveModuleState {
+    pub fn new(speed: f64, angle: Vec2) -> Self {
+        SwerveModuleState { speed, angle }
+    }
+
+    // drive backwards instead of steering more than a quarter turn
+    pub fn optimize(&self, current_angle: Vec2) -> Self {
+        let delta = Translation::rotate_by(self.angle, current_angle.inverse()).as_radians();
+
+        if delta.abs() > FRAC_PI_2 {
+            SwerveModuleState::new(-self.speed, self.angle.scale(-1.))
+        } else {
+            *self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwerveModuleStates {
+    pub time: f64,
+    pub modules: Vec<SwerveModuleState>,
+}
+
+// Module offsets are robot-relative, x forward and y to the left.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwerveDriveKinematics {
+    modules: Vec<Vec2>,
+}
+
+impl SwerveDriveKinematics {
+    pub fn new(modules: Vec<Vec2>) -> Self {
+        SwerveDriveKinematics { modules }
+    }
+
+    pub fn modules(&self) -> &[Vec2] {
+        &self.modules
+    }
+
+    // Modules that are not moving report a zero angle; pass the result
+    // through optimize against the measured angles before commanding it.
+    pub fn to_module_states(&self, speeds: &ChassisSpeeds) -> Vec<SwerveModuleState> {
+        self.modules
+            .iter()
+            .map(|offset| {
+                let velocity = [
+                    speeds.vx - speeds.omega * offset.y(),
+                    speeds.vy + speeds.omega * offset.x(),
+                ];
+                let speed = velocity.norm();
+
+                if speed < 1e-9 {
+                    SwerveModuleState::new(0., [1., 0.])
+                } else {
+                    SwerveModuleState::new(speed, velocity.scale(1. / speed))
+                }
+            })
+            .collect()
+    }
+
+    // Least-squares fit of the chassis speeds to the module states, via the
+    // normal equations of the 2N x 3 inverse kinematics matrix.
+    pub fn to_chassis_speeds(&self, states: &[SwerveModuleState]) -> Option<ChassisSpeeds> {
+        let mut normal: Mat3 = [[0.; 3]; 3];
+        let mut rhs: Vec3 = [0.; 3];
+
+        for (offset, state) in self.modules.iter().zip(states) {
+            let velocity = state.angle.scale(state.speed);
+            let rows: [Vec3; 2] = [[1., 0., -offset.y()], [0., 1., offset.x()]];
+
+            for (row, v) in rows.iter().zip(&[velocity.x(), velocity.y()]) {
+                for i in 0..3 {
+                    normal[i] = normal[i].add(row.scale(row[i]));
+                }
+                rhs = rhs.add(row.scale(*v));
+            }
+        }
+
+        let [vx, vy, omega] = normal.inverse()?.mul(&rhs);
+        Some(ChassisSpeeds::new(vx, vy, omega))
+    }
+
+    // scales every module down together so the fastest runs at max_speed
+    pub fn desaturate(states: &mut [SwerveModuleState], max_speed: f64) {
+        let fastest = states
+            .iter()
+            .map(|state| state.speed.abs())
+            .fold(0., f64::max);
+
+        if fastest > max_speed {
+            for state in states.iter_mut() {
+                state.speed *= max_speed / fastest;
+            }
+        }
+    }
+
+    // Per-module setpoints along a trajectory. Each module is optimized
+    // against its previous angle and holds that angle while stopped, starting
+    // out along the direction of travel.
+    pub fn module_states(
+        &self,
+        trajectory: &Trajectory,
+        max_speed: f64,
+    ) -> Vec<SwerveModuleStates> {
+        let mut out: Vec<SwerveModuleStates> = Vec::with_capacity(trajectory.states().len());
+
+        for state in trajectory.states() {
+            let mut modules = self.to_module_states(&ChassisSpeeds::from_state(state));
+            SwerveDriveKinematics::desaturate(&mut modules, max_speed);
+
+            match out.last() {
+                Some(previous) => {
+                    for (module, previous) in modules.iter_mut().zip(&previous.modules) {
+                        *module = if module.speed.abs() < 1e-9 {
+                            SwerveModuleState::new(0., previous.angle)
+                        } else {
+                            module.optimize(previous.angle)
+                        };
+                    }
+                }
+                None => {
+                    // start pointed along the direction of travel
+                    let travel = Translation::rotate_by(
+                        state.pose.rotation(),
+                        state.holonomic_rotation.inverse(),
+                    );
+                    for module in modules.iter_mut().filter(|m| m.speed.abs() < 1e-9) {
+                        module.angle = travel;
+                    }
+                }
+            }
+
+            out.push(SwerveModuleStates {
+                time: state.time,
+                modules,
+            });
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPS: f64 = 1e-9;
+
+    fn square() -> SwerveDriveKinematics {
+        SwerveDriveKinematics::new(vec![[0.3, 0.3], [0.3, -0.3], [-0.3, 0.3], [-0.3, -0.3]])
+    }
+
+    #[test]
+    fn round_trips_chassis_speeds() {
+        let kinematics = square();
+
+        for &(vx, vy, omega) in &[(1., 0., 0.), (0., -2., 0.), (0., 0., 3.), (1., 0.5, 0.3)] {
+            let states = kinematics.to_module_states(&ChassisSpeeds::new(vx, vy, omega));
+            let speeds = kinematics.to_chassis_speeds(&states).unwrap();
+
+            assert!((speeds.vx - vx).abs() < EPS);
+            assert!((speeds.vy - vy).abs() < EPS);
+            assert!((speeds.omega - omega).abs() < EPS);
+        }
+    }
+
+    #[test]
+    fn spinning_in_place_turns_every_module_tangentially() {
+        let kinematics = square();
+        let states = kinematics.to_module_states(&ChassisSpeeds::new(0., 0., 1.));
+
+        for (offset, state) in kinematics.modules().iter().zip(&states) {
+            assert!((state.speed - offset.norm()).abs() < EPS);
+            assert!(state.angle.dot(offset).abs() < EPS);
+        }
+    }
+
+    #[test]
+    fn one_module_cannot_recover_rotation() {
+        let kinematics = SwerveDriveKinematics::new(vec![[0.3, 0.3]]);
+        let states = kinematics.to_module_states(&ChassisSpeeds::new(1., 0., 0.));
+
+        assert!(kinematics.to_chassis_speeds(&states).is_none());
+    }
+
+    #[test]
+    fn optimize_reverses_instead_of_turning_around() {
+        let state = SwerveModuleState::new(2., [-1., 0.]);
+        let optimized = state.optimize([1., 0.]);
+
+        assert_eq!(optimized.speed, -2.);
+        assert!((optimized.angle.x() - 1.).abs() < EPS);
+        assert_eq!(state.optimize([0., 1.]).speed, 2.);
+    }
+
+    #[test]
+    fn desaturate_caps_the_fastest_module_and_keeps_ratios() {
+        let mut states = square().to_module_states(&ChassisSpeeds::new(3., 1., 4.));
+        let before: Vec<f64> = states.iter().map(|state| state.speed).collect();
+        let scale = 2. / before.iter().cloned().fold(0., f64::max);
+        SwerveDriveKinematics::desaturate(&mut states, 2.);
+
+        let fastest = states
+            .iter()
+            .map(|state| state.speed.abs())
+            .fold(0., f64::max);
+        assert!((fastest - 2.).abs() < EPS);
+        for (state, speed) in states.iter().zip(&before) {
+            assert!((state.speed - speed * scale).abs() < EPS);
+        }
+    }
+
+    #[test]
+    fn desaturate_leaves_slow_modules_alone() {
+        let mut states = square().to_module_states(&ChassisSpeeds::new(0.5, 0., 0.));
+        SwerveDriveKinematics::desaturate(&mut states, 2.);
+
+        assert!(states.iter().all(|state| (state.speed - 0.5).abs() < EPS));
+    }
+}
@@ -1,11 +1,23 @@
+mod constraint;
+mod error;
+mod kinematics;
 mod math;
+mod optimize;
 mod parameterizer;
+mod path;
 mod point;
 mod spline;
+mod trajectory;
 mod waypoint;
 
+pub use constraint::*;
+pub use error::*;
+pub use kinematics::*;
 pub use math::*;
+pub use optimize::*;
 pub use parameterizer::*;
+pub use path::*;
 pub use point::*;
 pub use spline::*;
+pub use trajectory::*;
 pub use waypoint::*;
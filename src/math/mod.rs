@@ -1,3 +1,7 @@
+mod pose;
+
+pub use pose::*;
+
 pub trait Vector {
     fn add(&self, rhs: Self) -> Self;
     fn scale(&self, rhs: f64) -> Self;
@@ -18,6 +22,19 @@ pub trait Vector {
         let scalar = 1. / self.norm();
         self.scale(scalar)
     }
+
+    // None for a zero or non-finite vector, which has no direction
+    fn try_unit(&self) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let norm = self.norm();
+        if norm > 0. && norm.is_finite() {
+            Some(self.scale(1. / norm))
+        } else {
+            None
+        }
+    }
 }
 
 pub trait Coordinate {
@@ -25,6 +42,20 @@ pub trait Coordinate {
     fn y(&self) -> f64;
 }
 
+impl Vector for f64 {
+    fn add(&self, rhs: Self) -> Self {
+        self + rhs
+    }
+
+    fn scale(&self, rhs: f64) -> Self {
+        self * rhs
+    }
+
+    fn dot(&self, rhs: &Self) -> f64 {
+        self * rhs
+    }
+}
+
 pub type Vec2 = [f64; 2];
 
 impl Vector for Vec2 {
@@ -51,6 +82,43 @@ impl Coordinate for Vec2 {
     }
 }
 
+pub type Vec3 = [f64; 3];
+
+impl Vector for Vec3 {
+    fn add(&self, rhs: Self) -> Self {
+        [self[0] + rhs[0], self[1] + rhs[1], self[2] + rhs[2]]
+    }
+
+    fn scale(&self, rhs: f64) -> Self {
+        [self[0] * rhs, self[1] * rhs, self[2] * rhs]
+    }
+
+    fn dot(&self, rhs: &Self) -> f64 {
+        self[0] * rhs[0] + self[1] * rhs[1] + self[2] * rhs[2]
+    }
+}
+
+pub type Vec4 = [f64; 4];
+
+impl Vector for Vec4 {
+    fn add(&self, rhs: Self) -> Self {
+        [
+            self[0] + rhs[0],
+            self[1] + rhs[1],
+            self[2] + rhs[2],
+            self[3] + rhs[3],
+        ]
+    }
+
+    fn scale(&self, rhs: f64) -> Self {
+        [self[0] * rhs, self[1] * rhs, self[2] * rhs, self[3] * rhs]
+    }
+
+    fn dot(&self, rhs: &Self) -> f64 {
+        self[0] * rhs[0] + self[1] * rhs[1] + self[2] * rhs[2] + self[3] * rhs[3]
+    }
+}
+
 pub type Vec6 = [f64; 6];
 
 impl Vector for Vec6 {
@@ -162,18 +230,19 @@ impl MatMul<Vec6> for Mat2x6 {
 
 pub type Mat6 = [Vec6; 6];
 
+// stored column by column, like Mat2x6
 impl Matrix for Mat6 {
     type Row = Vec6;
     type Col = Vec6;
 
     fn row(&self, index: usize) -> Self::Row {
         [
-            self[index][0],
-            self[index][1],
-            self[index][2],
-            self[index][3],
-            self[index][4],
-            self[index][5],
+            self[0][index],
+            self[1][index],
+            self[2][index],
+            self[3][index],
+            self[4][index],
+            self[5][index],
         ]
     }
 
@@ -182,6 +251,21 @@ impl Matrix for Mat6 {
     }
 }
 
+impl MatMul<Vec6> for Mat6 {
+    type Output = Vec6;
+
+    fn mul(&self, rhs: &Vec6) -> Self::Output {
+        [
+            self.row(0).dot(rhs),
+            self.row(1).dot(rhs),
+            self.row(2).dot(rhs),
+            self.row(3).dot(rhs),
+            self.row(4).dot(rhs),
+            self.row(5).dot(rhs),
+        ]
+    }
+}
+
 impl MatMul<Mat6> for Mat2x6 {
     type Output = Mat2x6;
 
@@ -199,7 +283,61 @@ impl MatMul<Mat6> for Mat2x6 {
     }
 }
 
-#[allow(dead_code)]
+pub type Mat2x4 = [Vec2; 4];
+
+impl Matrix for Mat2x4 {
+    type Row = Vec4;
+    type Col = Vec2;
+
+    fn row(&self, index: usize) -> Self::Row {
+        [
+            self[0][index],
+            self[1][index],
+            self[2][index],
+            self[3][index],
+        ]
+    }
+
+    fn col(&self, index: usize) -> Self::Col {
+        self[index]
+    }
+}
+
+impl MatMul<Vec4> for Mat2x4 {
+    type Output = Vec2;
+
+    fn mul(&self, other: &Vec4) -> Self::Output {
+        [
+            self[0][0] * other[0]
+                + self[1][0] * other[1]
+                + self[2][0] * other[2]
+                + self[3][0] * other[3],
+            self[0][1] * other[0]
+                + self[1][1] * other[1]
+                + self[2][1] * other[2]
+                + self[3][1] * other[3],
+        ]
+    }
+}
+
+pub type Mat4 = [Vec4; 4];
+
+impl MatMul<Mat4> for Mat2x4 {
+    type Output = Mat2x4;
+
+    fn mul(&self, rhs: &Mat4) -> Self::Output {
+        let up = self.row(0);
+        let down = self.row(1);
+        [
+            [up.dot(&rhs[0]), down.dot(&rhs[0])],
+            [up.dot(&rhs[1]), down.dot(&rhs[1])],
+            [up.dot(&rhs[2]), down.dot(&rhs[2])],
+            [up.dot(&rhs[3]), down.dot(&rhs[3])],
+        ]
+    }
+}
+
+// stored row by row
 pub type Mat2 = [Vec2; 2];
 
 impl Matrix for Mat2 {
@@ -207,11 +345,11 @@ impl Matrix for Mat2 {
     type Col = Vec2;
 
     fn row(&self, index: usize) -> Self::Row {
-        [self[index][0], self[index][1]]
+        self[index]
     }
 
     fn col(&self, index: usize) -> Self::Col {
-        self[index]
+        [self[0][index], self[1][index]]
     }
 
     fn determinant(&self) -> f64 {
@@ -242,9 +380,71 @@ impl MatMul<Vec2> for Mat2 {
     }
 }
 
+// stored row by row
+pub type Mat3 = [Vec3; 3];
+
+impl Matrix for Mat3 {
+    type Row = Vec3;
+    type Col = Vec3;
+
+    fn row(&self, index: usize) -> Self::Row {
+        self[index]
+    }
+
+    fn col(&self, index: usize) -> Self::Col {
+        [self[0][index], self[1][index], self[2][index]]
+    }
+
+    fn determinant(&self) -> f64 {
+        self[0][0] * (self[1][1] * self[2][2] - self[1][2] * self[2][1])
+            - self[0][1] * (self[1][0] * self[2][2] - self[1][2] * self[2][0])
+            + self[0][2] * (self[1][0] * self[2][1] - self[1][1] * self[2][0])
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+
+        if det == 0. {
+            return None;
+        }
+
+        // transposed cofactors over the determinant
+        let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+            (self[r0][c0] * self[r1][c1] - self[r0][c1] * self[r1][c0]) / det
+        };
+
+        Some([
+            [
+                cofactor(1, 2, 1, 2),
+                cofactor(0, 2, 2, 1),
+                cofactor(0, 1, 1, 2),
+            ],
+            [
+                cofactor(1, 2, 2, 0),
+                cofactor(0, 2, 0, 2),
+                cofactor(0, 1, 2, 0),
+            ],
+            [
+                cofactor(1, 2, 0, 1),
+                cofactor(0, 2, 1, 0),
+                cofactor(0, 1, 0, 1),
+            ],
+        ])
+    }
+}
+
+impl MatMul<Vec3> for Mat3 {
+    type Output = Vec3;
+
+    fn mul(&self, rhs: &Vec3) -> Self::Output {
+        [self[0].dot(rhs), self[1].dot(rhs), self[2].dot(rhs)]
+    }
+}
+
 pub trait Rotation {
     fn inverse(self) -> Self;
     fn rotate_by(self, other: Self) -> Self;
+    #[allow(clippy::wrong_self_convention)]
     fn as_radians(self) -> f64;
     fn from_radians(rad: f64) -> Self;
 }
@@ -263,7 +463,7 @@ impl Rotation for Vec2 {
     }
 
     fn from_radians(rad: f64) -> Self {
-        let (x, y) = rad.sin_cos();
+        let (y, x) = rad.sin_cos();
         [x, y]
     }
 }
@@ -281,52 +481,409 @@ impl Translation for Vec2 {
     }
 }
 
-pub struct Pose {
-    t: Vec2,
-    r: Vec2,
-}
-
-impl Pose {
-    pub fn new(t: Vec2, r: Vec2) -> Self {
-        Pose { t, r }
-    }
+pub fn solve_tridiagonal<V: Vector + Copy>(
+    lower: &[f64],
+    diag: &[f64],
+    upper: &[f64],
+    rhs: &[V],
+) -> Vec<V> {
+    // Thomas algorithm; lower[0] and upper[n - 1] are ignored
+    let n = diag.len();
+    let mut c = vec![0.; n];
+    let mut d = rhs.to_vec();
+
+    for i in 0..n {
+        let denom = if i == 0 {
+            diag[0]
+        } else {
+            diag[i] - lower[i] * c[i - 1]
+        };
 
-    pub fn log(self) -> Twist {
-        let dt = self.r.as_radians();
-        let half_dt = dt / 2.;
-        let cos_minus_one = self.r.x() - 1.;
-        let halftheta_by_tan_of_halfdtheta = if cos_minus_one.abs() < 1e-9 {
-            1. - 1. / 12. * dt * dt
+        c[i] = upper[i] / denom;
+        d[i] = if i == 0 {
+            d[0].scale(1. / denom)
         } else {
-            -(half_dt * self.r.y()) / cos_minus_one
+            d[i].add(d[i - 1].scale(-lower[i])).scale(1. / denom)
         };
+    }
 
-        let rot = [halftheta_by_tan_of_halfdtheta, -half_dt].unit();
+    for i in (0..n.saturating_sub(1)).rev() {
+        d[i] = d[i].add(d[i + 1].scale(-c[i]));
+    }
 
-        let t_part = Translation::rotate_by(self.t, rot);
+    d
+}
 
-        Twist {
-            ds: t_part,
-            dt: rot,
-        }
+// adaptive Simpson quadrature; `tol` bounds the absolute error, measured with
+// Vector::norm so vector-valued integrands are refined as a whole. Refinement
+// stops early once MAX_EVALS evaluations are spent, so an unreachable
+// tolerance costs a bounded amount of work instead of exhausting MAX_DEPTH.
+pub fn adaptive_simpson<V: Vector + Copy, F: Fn(f64) -> V>(f: F, a: f64, b: f64, tol: f64) -> V {
+    const MAX_EVALS: usize = 100_000;
+
+    let fa = f(a);
+    let fm = f((a + b) / 2.);
+    let fb = f(b);
+    let whole = simpson(a, b, fa, fm, fb);
+
+    let mut budget = MAX_EVALS - 3;
+    simpson_step(&f, a, b, fa, fm, fb, whole, tol, 0, &mut budget)
+}
+
+fn simpson<V: Vector + Copy>(a: f64, b: f64, fa: V, fm: V, fb: V) -> V {
+    fa.add(fm.scale(4.)).add(fb).scale((b - a) / 6.)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn simpson_step<V: Vector + Copy, F: Fn(f64) -> V>(
+    f: &F,
+    a: f64,
+    b: f64,
+    fa: V,
+    fm: V,
+    fb: V,
+    whole: V,
+    tol: f64,
+    depth: u32,
+    budget: &mut usize,
+) -> V {
+    const MIN_DEPTH: u32 = 3;
+    const MAX_DEPTH: u32 = 20;
+
+    let m = (a + b) / 2.;
+    let flm = f((a + m) / 2.);
+    let frm = f((m + b) / 2.);
+    *budget = budget.saturating_sub(2);
+    let left = simpson(a, m, fa, flm, fm);
+    let right = simpson(m, b, fm, frm, fb);
+    let delta = left.add(right).add(whole.scale(-1.));
+
+    // a non-finite estimate never converges, so hand it straight back
+    let error = delta.norm();
+    let converged = depth >= MIN_DEPTH && error <= 15. * tol;
+    if !error.is_finite() || depth >= MAX_DEPTH || *budget == 0 || converged {
+        // Richardson extrapolation
+        left.add(right).add(delta.scale(1. / 15.))
+    } else {
+        // the left half may only spend half of what is left, so a hard left
+        // half can't starve the right one
+        let mut left_budget = *budget / 2;
+        let rest = *budget - left_budget;
+        let left = simpson_step(
+            f,
+            a,
+            m,
+            fa,
+            flm,
+            fm,
+            left,
+            tol / 2.,
+            depth + 1,
+            &mut left_budget,
+        );
+        *budget = rest + left_budget;
+        let right = simpson_step(f, m, b, fm, frm, fb, right, tol / 2., depth + 1, budget);
+        left.add(right)
     }
 }
 
-pub struct Twist {
-    ds: Vec2,
-    dt: Vec2,
+// Fresnel integrals (C(x), S(x)) with the pi / 2 * t^2 convention: a power
+// series near zero and a continued fraction (Lentz's method) beyond that,
+// after Numerical Recipes. Complex values are carried as Vec2.
+pub fn fresnel(x: f64) -> (f64, f64) {
+    const EPS: f64 = 1e-15;
+    const MAX_ITERS: usize = 200;
+    const FPMIN: f64 = 1e-300;
+    const XMIN: f64 = 1.5;
+
+    let ax = x.abs();
+    let (c, s) = if ax < FPMIN.sqrt() {
+        (ax, 0.)
+    } else if ax <= XMIN {
+        let fact = std::f64::consts::FRAC_PI_2 * ax * ax;
+        let (mut sum, mut sums, mut sumc) = (0., 0., ax);
+        let mut sign = 1.;
+        let mut term = ax;
+        let mut odd = true;
+        let mut n = 3.;
+
+        for k in 1..MAX_ITERS {
+            term *= fact / k as f64;
+            sum += sign * term / n;
+            let test = sum.abs() * EPS;
+            if odd {
+                sign = -sign;
+                sums = sum;
+                sum = sumc;
+            } else {
+                sumc = sum;
+                sum = sums;
+            }
+            if term < test {
+                break;
+            }
+            odd = !odd;
+            n += 2.;
+        }
+
+        (sumc, sums)
+    } else {
+        let complex_inverse = |z: Vec2| z.inverse().scale(1. / z.norm_squared());
+
+        let pix2 = std::f64::consts::PI * ax * ax;
+        let mut b = [1., -pix2];
+        let mut cc = [1. / FPMIN, 0.];
+        let mut d = complex_inverse(b);
+        let mut h = d;
+        let mut n = -1.;
+
+        for _ in 2..MAX_ITERS {
+            n += 2.;
+            let a = -n * (n + 1.);
+            b = b.add([4., 0.]);
+            d = complex_inverse(d.scale(a).add(b));
+            cc = b.add(complex_inverse(cc).scale(a));
+            let del = Translation::rotate_by(cc, d);
+            h = Translation::rotate_by(h, del);
+            if (del.x() - 1.).abs() + del.y().abs() < EPS {
+                break;
+            }
+        }
+
+        h = Translation::rotate_by(h, [ax, -ax]);
+        let (sin, cos) = (0.5 * pix2).sin_cos();
+        let cs = Translation::rotate_by(
+            [0.5, 0.5],
+            [1., 0.].add(Translation::rotate_by([cos, sin], h).scale(-1.)),
+        );
+
+        (cs.x(), cs.y())
+    };
+
+    if x < 0. {
+        (-c, -s)
+    } else {
+        (c, s)
+    }
 }
 
-impl Twist {
-    pub fn dx(&self) -> f64 {
-        self.ds.x()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::f64::consts::PI;
+
+    const EPS: f64 = 1e-9;
+
+    fn angle_eq(a: f64, b: f64) -> bool {
+        let diff = (a - b).rem_euclid(2. * PI);
+        diff < EPS || 2. * PI - diff < EPS
+    }
+
+    fn vec2_eq(a: Vec2, b: Vec2, eps: f64) -> bool {
+        (a.x() - b.x()).abs() < eps && (a.y() - b.y()).abs() < eps
+    }
+
+    fn arb_vec2() -> impl Strategy<Value = Vec2> {
+        [-100.0..100.0, -100.0..100.0]
+    }
+
+    fn arb_vec3() -> impl Strategy<Value = Vec3> {
+        [-10.0..10.0, -10.0..10.0, -10.0..10.0]
+    }
+
+    fn arb_vec6() -> impl Strategy<Value = Vec6> {
+        [
+            -10.0..10.0,
+            -10.0..10.0,
+            -10.0..10.0,
+            -10.0..10.0,
+            -10.0..10.0,
+            -10.0..10.0,
+        ]
+    }
+
+    fn arb_angle() -> impl Strategy<Value = f64> {
+        -PI + 1e-6..PI - 1e-6
+    }
+
+    #[test]
+    fn from_radians_is_cos_sin() {
+        assert!(vec2_eq(Vec2::from_radians(0.), [1., 0.], EPS));
+        assert!(vec2_eq(Vec2::from_radians(PI / 2.), [0., 1.], EPS));
+        assert!(vec2_eq(Vec2::from_radians(PI), [-1., 0.], EPS));
     }
 
-    pub fn dy(&self) -> f64 {
-        self.ds.y()
+    #[test]
+    fn mat2x6_row_col_agree() {
+        let m: Mat2x6 = [[0., 1.], [2., 3.], [4., 5.], [6., 7.], [8., 9.], [10., 11.]];
+        for i in 0..2 {
+            for j in 0..6 {
+                assert_eq!(m.row(i)[j], m.col(j)[i]);
+            }
+        }
     }
 
-    pub fn dt(&self) -> f64 {
-        self.dt.as_radians()
+    #[test]
+    fn mat6_row_col_agree() {
+        let mut m: Mat6 = [[0.; 6]; 6];
+        for (i, col) in m.iter_mut().enumerate() {
+            for (j, x) in col.iter_mut().enumerate() {
+                *x = (6 * i + j) as f64;
+            }
+        }
+        for i in 0..6 {
+            for j in 0..6 {
+                assert_eq!(m.row(i)[j], m.col(j)[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn try_unit_rejects_zero() {
+        assert_eq!([0., 0.].try_unit(), None);
+        assert_eq!([f64::INFINITY, 0.].try_unit(), None);
+        assert_eq!([0., 2.].try_unit(), Some([0., 1.]));
+    }
+
+    #[test]
+    fn adaptive_simpson_returns_nan_quickly() {
+        let calls = std::cell::Cell::new(0);
+        let integral = adaptive_simpson(
+            |_| {
+                calls.set(calls.get() + 1);
+                [f64::NAN, 0.]
+            },
+            0.,
+            1.,
+            1e-13,
+        );
+        assert!(integral[0].is_nan());
+        assert_eq!(calls.get(), 5);
+    }
+
+    #[test]
+    fn adaptive_simpson_stops_at_its_budget() {
+        let calls = std::cell::Cell::new(0);
+        // a jump the tolerance can never resolve
+        let integral = adaptive_simpson(
+            |x: f64| {
+                calls.set(calls.get() + 1);
+                [if x < 1. / 3. { 0. } else { 1. }, 0.]
+            },
+            0.,
+            1.,
+            1e-300,
+        );
+        assert!((integral[0] - 2. / 3.).abs() < 1e-3);
+        assert!(calls.get() <= 100_000);
+    }
+
+    #[test]
+    fn mat2_row_col_agree() {
+        let m: Mat2 = [[1., 2.], [3., 4.]];
+        for i in 0..2 {
+            for j in 0..2 {
+                assert_eq!(m.row(i)[j], m.col(j)[i]);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn add_commutes(a in arb_vec2(), b in arb_vec2()) {
+            prop_assert!(vec2_eq(a.add(b), b.add(a), EPS));
+        }
+
+        #[test]
+        fn scale_distributes(a in arb_vec2(), b in arb_vec2(), k in -10.0..10.0) {
+            prop_assert!(vec2_eq(a.add(b).scale(k), a.scale(k).add(b.scale(k)), 1e-9));
+        }
+
+        #[test]
+        fn dot_is_symmetric(a in arb_vec6(), b in arb_vec6()) {
+            prop_assert!((a.dot(&b) - b.dot(&a)).abs() < EPS);
+        }
+
+        #[test]
+        fn unit_has_norm_one(a in arb_vec2()) {
+            prop_assume!(a.norm() > 1e-6);
+            prop_assert!((a.unit().norm() - 1.).abs() < EPS);
+        }
+
+        #[test]
+        fn radians_round_trip(theta in arb_angle()) {
+            prop_assert!(angle_eq(Vec2::from_radians(theta).as_radians(), theta));
+        }
+
+        #[test]
+        fn rotations_compose(a in arb_angle(), b in arb_angle()) {
+            let r = Rotation::rotate_by(Vec2::from_radians(a), Vec2::from_radians(b));
+            prop_assert!(angle_eq(r.as_radians(), a + b));
+        }
+
+        #[test]
+        fn rotation_inverse_cancels(theta in arb_angle()) {
+            let r = Vec2::from_radians(theta);
+            prop_assert!(angle_eq(Rotation::rotate_by(r, r.inverse()).as_radians(), 0.));
+        }
+
+        #[test]
+        fn translation_rotation_matches_rotation(v in arb_vec2(), theta in arb_angle()) {
+            prop_assume!(v.norm() > 1e-6);
+            let r = Vec2::from_radians(theta);
+            let rotated = Translation::rotate_by(v, r);
+            prop_assert!((rotated.norm() - v.norm()).abs() < 1e-9);
+            prop_assert!(angle_eq(rotated.as_radians(), v.as_radians() + theta));
+            prop_assert!(vec2_eq(
+                Translation::rotate_by(v.unit(), r),
+                Rotation::rotate_by(v.unit(), r),
+                1e-9
+            ));
+        }
+
+        #[test]
+        fn mat2_inverse_undoes_mul(m in [arb_vec2(), arb_vec2()], v in arb_vec2()) {
+            prop_assume!(m.determinant().abs() > 1e-3);
+            let inv = Matrix::inverse(&m).unwrap();
+            prop_assert!(vec2_eq(inv.mul(&m.mul(&v)), v, 1e-6));
+            prop_assert!(vec2_eq(m.mul(&inv.mul(&v)), v, 1e-6));
+        }
+
+        #[test]
+        fn mat3_inverse_undoes_mul(m in [arb_vec3(), arb_vec3(), arb_vec3()], v in arb_vec3()) {
+            prop_assume!(m.determinant().abs() > 1e-2);
+            let inv = Matrix::inverse(&m).unwrap();
+            let back: Vec3 = inv.mul(&m.mul(&v));
+            prop_assert!(back.add(v.scale(-1.)).norm() < 1e-6);
+        }
+
+        #[test]
+        fn mat2x6_mul_vec6_is_column_sum(m in [arb_vec2(), arb_vec2(), arb_vec2(), arb_vec2(), arb_vec2(), arb_vec2()], v in arb_vec6()) {
+            let expected = (0..6).fold([0., 0.], |acc, k| acc.add(m.col(k).scale(v[k])));
+            prop_assert!(vec2_eq(m.mul(&v), expected, 1e-9));
+        }
+
+        #[test]
+        fn mat2x6_mul_mat6_associates(
+            a in [arb_vec2(), arb_vec2(), arb_vec2(), arb_vec2(), arb_vec2(), arb_vec2()],
+            b in [arb_vec6(), arb_vec6(), arb_vec6(), arb_vec6(), arb_vec6(), arb_vec6()],
+            v in arb_vec6(),
+        ) {
+            prop_assert!(vec2_eq(a.mul(&b).mul(&v), a.mul(&b.mul(&v)), 1e-6));
+        }
+
+        #[test]
+        fn pose_log_of_translation_is_translation(t in arb_vec2()) {
+            let twist = Pose::new(t, Vec2::from_radians(0.)).log();
+            prop_assert!(vec2_eq([twist.dx(), twist.dy()], t, 1e-9));
+            prop_assert!(twist.dt().abs() < EPS);
+        }
+
+        #[test]
+        fn pose_log_heading(t in arb_vec2(), theta in arb_angle()) {
+            let twist = Pose::new(t, Vec2::from_radians(theta)).log();
+            prop_assert!(angle_eq(twist.dt(), theta));
+        }
     }
 }
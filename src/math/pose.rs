@@ -0,0 +1,225 @@
+use crate::{Coordinate, Rotation, Translation, Vec2, Vector};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Pose {
+    t: Vec2,
+    r: Vec2,
+}
+
+impl Pose {
+    pub fn new(t: Vec2, r: Vec2) -> Self {
+        Pose { t, r }
+    }
+
+    pub fn identity() -> Self {
+        Pose::new([0., 0.], [1., 0.])
+    }
+
+    pub fn translation(&self) -> Vec2 {
+        self.t
+    }
+
+    pub fn rotation(&self) -> Vec2 {
+        self.r
+    }
+
+    pub fn x(&self) -> f64 {
+        self.t.x()
+    }
+
+    pub fn y(&self) -> f64 {
+        self.t.y()
+    }
+
+    pub fn heading(&self) -> f64 {
+        self.r.as_radians()
+    }
+
+    pub fn exp(twist: &Twist) -> Pose {
+        let dt = twist.dt;
+        let (sin_theta, cos_theta) = dt.sin_cos();
+        let (s, c) = if dt.abs() < 1e-9 {
+            (1. - 1. / 6. * dt * dt, 0.5 * dt)
+        } else {
+            (sin_theta / dt, (1. - cos_theta) / dt)
+        };
+
+        Pose {
+            t: [
+                twist.dx() * s - twist.dy() * c,
+                twist.dx() * c + twist.dy() * s,
+            ],
+            r: [cos_theta, sin_theta],
+        }
+    }
+
+    pub fn log(&self) -> Twist {
+        let dt = self.r.as_radians();
+        let half_dt = dt / 2.;
+        // cos(dt) - 1 without cancellation for small rotations
+        let cos_minus_one = -2. * half_dt.sin().powi(2);
+        let halftheta_by_tan_of_halfdtheta = if cos_minus_one.abs() < 1e-9 {
+            1. - 1. / 12. * dt * dt
+        } else {
+            -(half_dt * self.r.y()) / cos_minus_one
+        };
+
+        // deliberately not normalized: its length converts chord to arc length
+        let t_part = Translation::rotate_by(self.t, [halftheta_by_tan_of_halfdtheta, -half_dt]);
+
+        Twist { ds: t_part, dt }
+    }
+
+    pub fn compose(&self, other: &Pose) -> Pose {
+        Pose {
+            t: self.t.add(Translation::rotate_by(other.t, self.r)),
+            r: Translation::rotate_by(other.r, self.r).unit(),
+        }
+    }
+
+    pub fn inverse(&self) -> Pose {
+        let r = self.r.inverse();
+
+        Pose {
+            t: Translation::rotate_by(self.t, r).scale(-1.),
+            r,
+        }
+    }
+
+    pub fn relative_to(&self, other: &Pose) -> Pose {
+        other.inverse().compose(self)
+    }
+
+    pub fn interpolate(&self, other: &Pose, x: f64) -> Pose {
+        let twist = other.relative_to(self).log();
+        self.compose(&Pose::exp(&twist.scale(x)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Twist {
+    ds: Vec2,
+    dt: f64,
+}
+
+impl Twist {
+    pub fn new(dx: f64, dy: f64, dt: f64) -> Self {
+        Twist { ds: [dx, dy], dt }
+    }
+
+    pub fn dx(&self) -> f64 {
+        self.ds.x()
+    }
+
+    pub fn dy(&self) -> f64 {
+        self.ds.y()
+    }
+
+    pub fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    pub fn scale(&self, x: f64) -> Twist {
+        Twist {
+            ds: self.ds.scale(x),
+            dt: self.dt * x,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::f64::consts::PI;
+
+    const EPS: f64 = 1e-9;
+
+    fn pose(x: f64, y: f64, theta: f64) -> Pose {
+        Pose::new([x, y], [theta.cos(), theta.sin()])
+    }
+
+    fn assert_pose_eq(a: &Pose, b: &Pose) {
+        assert!((a.x() - b.x()).abs() < EPS, "{:?} != {:?}", a, b);
+        assert!((a.y() - b.y()).abs() < EPS, "{:?} != {:?}", a, b);
+        assert!(
+            Translation::rotate_by(a.rotation(), b.rotation().inverse())
+                .as_radians()
+                .abs()
+                < EPS,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    prop_compose! {
+        fn arb_pose()(x in -100.0..100.0, y in -100.0..100.0, theta in -PI + 1e-6..PI - 1e-6) -> Pose {
+            pose(x, y, theta)
+        }
+    }
+
+    #[test]
+    fn exp_quarter_circle() {
+        // drive a quarter of a unit circle to the left
+        let p = Pose::exp(&Twist::new(PI / 2., 0., PI / 2.));
+        assert_pose_eq(&p, &pose(1., 1., PI / 2.));
+    }
+
+    #[test]
+    fn log_quarter_circle() {
+        let twist = pose(1., 1., PI / 2.).log();
+        assert!((twist.dx() - PI / 2.).abs() < EPS);
+        assert!(twist.dy().abs() < EPS);
+        assert!((twist.dt() - PI / 2.).abs() < EPS);
+    }
+
+    proptest! {
+        #[test]
+        fn exp_log_round_trip(p in arb_pose()) {
+            assert_pose_eq(&Pose::exp(&p.log()), &p);
+        }
+
+        #[test]
+        fn log_exp_round_trip(dx in -100.0..100.0, dy in -100.0..100.0, dt in -PI + 1e-6..PI - 1e-6) {
+            let twist = Pose::exp(&Twist::new(dx, dy, dt)).log();
+            prop_assert!((twist.dx() - dx).abs() < 1e-6);
+            prop_assert!((twist.dy() - dy).abs() < 1e-6);
+            prop_assert!((twist.dt() - dt).abs() < EPS);
+        }
+
+        #[test]
+        fn compose_with_inverse_is_identity(p in arb_pose()) {
+            assert_pose_eq(&p.compose(&p.inverse()), &Pose::identity());
+            assert_pose_eq(&p.inverse().compose(&p), &Pose::identity());
+        }
+
+        #[test]
+        fn compose_is_associative(a in arb_pose(), b in arb_pose(), c in arb_pose()) {
+            let left = a.compose(&b).compose(&c);
+            let right = a.compose(&b.compose(&c));
+            prop_assert!((left.x() - right.x()).abs() < 1e-6);
+            prop_assert!((left.y() - right.y()).abs() < 1e-6);
+        }
+
+        #[test]
+        fn relative_to_round_trip(a in arb_pose(), b in arb_pose()) {
+            let rel = b.relative_to(&a);
+            let back = a.compose(&rel);
+            prop_assert!((back.x() - b.x()).abs() < 1e-6);
+            prop_assert!((back.y() - b.y()).abs() < 1e-6);
+            prop_assert!((back.heading() - b.heading()).abs() < EPS
+                || (back.heading() - b.heading()).abs() > 2. * PI - EPS);
+        }
+
+        #[test]
+        fn interpolate_hits_endpoints(a in arb_pose(), b in arb_pose()) {
+            let start = a.interpolate(&b, 0.);
+            let end = a.interpolate(&b, 1.);
+            assert_pose_eq(&start, &a);
+            prop_assert!((end.x() - b.x()).abs() < 1e-6);
+            prop_assert!((end.y() - b.y()).abs() < 1e-6);
+        }
+    }
+}
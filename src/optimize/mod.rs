@@ -0,0 +1,231 @@
+use crate::{hermites_with, CurvatureGradient, HermiteOptions, Spline, Waypoint};
+use serde::{Deserialize, Serialize};
+
+// Gradient descent converges linearly, so min_gradient is what normally ends
+// the search; MaxIterations means the cost was still falling when the budget
+// ran out, and the waypoints hold the best curvatures found so far.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OptimizeOptions {
+    pub tolerance: f64,
+    pub max_iters: u64,
+    pub target_cost: f64,
+    pub min_gradient: f64,
+    pub hermite: HermiteOptions,
+}
+
+impl Default for OptimizeOptions {
+    fn default() -> Self {
+        OptimizeOptions {
+            tolerance: 1e-6,
+            max_iters: 500,
+            target_cost: 0.,
+            min_gradient: 1e-4,
+            hermite: HermiteOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Convergence {
+    // no interior waypoints, so there are no curvatures to adjust
+    NothingToOptimize,
+    TargetCost,
+    SmallGradient,
+    LineSearchFailed,
+    MaxIterations,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct OptimizeReport {
+    pub iterations: u64,
+    pub cost: f64,
+    pub reason: Convergence,
+}
+
+pub fn optimize_curvature(wps: &mut [Waypoint], opts: &OptimizeOptions) -> OptimizeReport {
+    if wps.len() <= 2 {
+        return OptimizeReport {
+            iterations: 0,
+            cost: curvature_cost(wps, &opts.hermite, opts.tolerance),
+            reason: Convergence::NothingToOptimize,
+        };
+    }
+
+    let mut param: Vec<f64> = wps[1..wps.len() - 1]
+        .iter()
+        .flat_map(|wp| wp.curvature.to_vec())
+        .collect();
+    let mut cost = cost_at(wps, &param, opts);
+    let mut step = 1.;
+
+    let mut iterations = 0;
+    let reason = loop {
+        if cost <= opts.target_cost {
+            break Convergence::TargetCost;
+        }
+        if iterations >= opts.max_iters {
+            break Convergence::MaxIterations;
+        }
+
+        let grad = gradient_at(wps, &param, opts);
+        let grad_norm_squared: f64 = grad.iter().map(|g| g * g).sum();
+        if grad_norm_squared.sqrt() < opts.min_gradient {
+            break Convergence::SmallGradient;
+        }
+
+        // backtracking line search with the Armijo condition, starting a bit
+        // further than the last accepted step
+        step *= 2.;
+        let accepted = loop {
+            let candidate: Vec<f64> = param
+                .iter()
+                .zip(grad.iter())
+                .map(|(p, g)| p - step * g)
+                .collect();
+            let candidate_cost = cost_at(wps, &candidate, opts);
+
+            if candidate_cost <= cost - 1e-4 * step * grad_norm_squared {
+                break Some((candidate, candidate_cost));
+            }
+
+            step /= 2.;
+            if step < 1e-16 {
+                break None;
+            }
+        };
+
+        iterations += 1;
+        match accepted {
+            Some((candidate, candidate_cost)) => {
+                param = candidate;
+                cost = candidate_cost;
+            }
+            None => break Convergence::LineSearchFailed,
+        }
+    };
+
+    apply(wps, &param);
+
+    OptimizeReport {
+        iterations,
+        cost,
+        reason,
+    }
+}
+
+pub fn curvature_cost(wps: &[Waypoint], hermite: &HermiteOptions, tol: f64) -> f64 {
+    hermites_with(wps, hermite)
+        .iter()
+        .map(|h| h.integral_d_curvature_d_t_squared(tol))
+        .sum()
+}
+
+// gradient of curvature_cost with respect to the curvature of every interior
+// waypoint, flattened as [x1, y1, x2, y2, ...]; each waypoint only sees the
+// segments on either side of it
+pub fn curvature_cost_gradient(wps: &[Waypoint], hermite: &HermiteOptions, tol: f64) -> Vec<f64> {
+    let segment_grads: Vec<_> = hermites_with(wps, hermite)
+        .iter()
+        .map(|h| h.integral_d_curvature_d_t_squared_gradient(tol))
+        .collect();
+
+    segment_grads
+        .iter()
+        .zip(segment_grads.iter().skip(1))
+        .flat_map(|(before, after)| vec![before[2] + after[0], before[3] + after[1]])
+        .collect()
+}
+
+fn apply(wps: &mut [Waypoint], param: &[f64]) {
+    wps.iter_mut()
+        .skip(1)
+        .zip(param.chunks(2))
+        .for_each(|(wp, acc)| wp.curvature = [acc[0], acc[1]]);
+}
+
+fn cost_at(wps: &[Waypoint], param: &[f64], opts: &OptimizeOptions) -> f64 {
+    let mut wps = wps.to_vec();
+    apply(&mut wps, param);
+    curvature_cost(&wps, &opts.hermite, opts.tolerance)
+}
+
+fn gradient_at(wps: &[Waypoint], param: &[f64], opts: &OptimizeOptions) -> Vec<f64> {
+    let mut wps = wps.to_vec();
+    apply(&mut wps, param);
+    curvature_cost_gradient(&wps, &opts.hermite, opts.tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wps() -> Vec<Waypoint> {
+        vec![
+            Waypoint::new(0., 0., 1., 0., 0., 0.),
+            Waypoint::new(2., 1., 1., 1., 0., 0.),
+            Waypoint::new(4., 0., 1., -1., 0., 0.),
+            Waypoint::new(6., 1., 1., 0., 0., 0.),
+        ]
+    }
+
+    #[test]
+    fn default_options_converge() {
+        let mut wps = wps();
+        let opts = OptimizeOptions::default();
+        let before = curvature_cost(&wps, &opts.hermite, opts.tolerance);
+        let report = optimize_curvature(&mut wps, &opts);
+
+        assert_eq!(report.reason, Convergence::SmallGradient);
+        assert!(report.iterations < opts.max_iters);
+        assert!(report.cost < before / 3.);
+        assert!((curvature_cost(&wps, &opts.hermite, opts.tolerance) - report.cost).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cost_never_increases() {
+        let costs: Vec<f64> = (0..12)
+            .map(|max_iters| {
+                let opts = OptimizeOptions {
+                    max_iters,
+                    ..OptimizeOptions::default()
+                };
+                optimize_curvature(&mut wps(), &opts).cost
+            })
+            .collect();
+
+        for (c0, c1) in costs.iter().zip(costs.iter().skip(1)) {
+            assert!(c1 <= c0);
+        }
+        assert!(costs[11] < costs[0]);
+    }
+
+    #[test]
+    fn path_gradient_matches_finite_differences() {
+        let hermite = HermiteOptions::default();
+        let tol = 1e-10;
+        let wps = wps();
+        let analytic = curvature_cost_gradient(&wps, &hermite, tol);
+        let h = 1e-5;
+
+        for (i, grad) in analytic.iter().enumerate() {
+            let nudged = |delta: f64| {
+                let mut wps = wps.clone();
+                wps[1 + i / 2].curvature[i % 2] += delta;
+                curvature_cost(&wps, &hermite, tol)
+            };
+            let numeric = (nudged(h) - nudged(-h)) / (2. * h);
+
+            assert!((grad - numeric).abs() < 1e-6 * numeric.abs().max(1.));
+        }
+    }
+
+    #[test]
+    fn two_waypoints_have_nothing_to_optimize() {
+        let mut wps = wps()[..2].to_vec();
+        let report = optimize_curvature(&mut wps, &OptimizeOptions::default());
+
+        assert_eq!(report.reason, Convergence::NothingToOptimize);
+        assert_eq!(report.iterations, 0);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Hermite, Parameterizer, Pose, Rotation, Spline, Translation, Vector};
+use crate::{Parameterizer, Pose, Spline};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -19,17 +19,13 @@ impl Cheesy {
 }
 
 impl Parameterizer for Cheesy {
-    fn should_subdivide(&self, spline: &Hermite, t_curr: f64, t_step: f64) -> bool {
+    fn should_subdivide<S: Spline>(&self, spline: &S, t_curr: f64, t_step: f64) -> bool {
         let p0 = spline.point_at(t_curr);
         let p1 = spline.point_at(t_curr + t_step);
-        let r0 = p0.heading();
-        let r1 = p1.heading();
 
-        let tf = Pose::new(
-            Translation::rotate_by(p0.position.scale(-1.).add(p1.position), r0.inverse()),
-            Rotation::rotate_by(r1, r0.inverse()),
-        );
-        let twist = tf.log();
+        let start = Pose::new(p0.position, p0.heading());
+        let end = Pose::new(p1.position, p1.heading());
+        let twist = end.relative_to(&start).log();
 
         twist.dx().abs() > self.max_dx
             || twist.dy().abs() > self.max_dy
@@ -1,4 +1,4 @@
-use crate::{Coordinate, Hermite, MatMul, Matrix, Parameterizer, Spline, Vec2, Vector};
+use crate::{Coordinate, MatMul, Matrix, Parameterizer, Spline, Vec2, Vector};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -14,7 +14,7 @@ impl Jaci {
 }
 
 impl Parameterizer for Jaci {
-    fn should_subdivide(&self, spline: &Hermite, t_curr: f64, t_step: f64) -> bool {
+    fn should_subdivide<S: Spline>(&self, spline: &S, t_curr: f64, t_step: f64) -> bool {
         let p0 = spline.position(t_curr);
         let p_mid = spline.position(t_curr + t_step / 2.0);
         let p1 = spline.position(t_curr + t_step);
@@ -4,25 +4,47 @@ mod jaci;
 pub use cheesy::*;
 pub use jaci::*;
 
-use crate::Hermite;
 use crate::Point;
 use crate::Spline;
+use crate::WayfinderError;
 
 pub trait Parameterizer {
-    fn should_subdivide(&self, spline: &Hermite, t_curr: f64, t_step: f64) -> bool;
+    fn should_subdivide<S: Spline>(&self, spline: &S, t_curr: f64, t_step: f64) -> bool;
 
-    fn parameterize(&self, splines: &[Hermite]) -> Vec<Point> {
+    fn parameterize<S: Spline>(&self, splines: &[S]) -> Vec<Point> {
         let mut pts = Vec::<Point>::new();
-        pts.push(splines.get(0).unwrap().point_at(0.));
+        if let Some(first) = splines.first() {
+            pts.push(first.point_at(0.));
+        }
 
         for spline in splines {
-            self.subdivide(&spline, &mut pts, 0., 1.);
+            self.subdivide(spline, &mut pts, 0., 1.);
         }
 
         pts
     }
 
-    fn subdivide(&self, spline: &Hermite, out: &mut Vec<Point>, t0: f64, t1: f64) {
+    fn try_parameterize<S: Spline>(&self, splines: &[S]) -> Result<Vec<Point>, WayfinderError> {
+        let first = splines.first().ok_or(WayfinderError::EmptyInput)?;
+
+        let mut pts = vec![first.point_at(0.)];
+        let mut checked = 0;
+        for (segment, spline) in splines.iter().enumerate() {
+            self.subdivide(spline, &mut pts, 0., 1.);
+
+            if !pts[checked..].iter().all(Point::is_finite) {
+                return Err(WayfinderError::NonFinitePoint { segment });
+            }
+            if pts[checked..].iter().any(Point::is_stationary) {
+                return Err(WayfinderError::StationaryPoint { segment });
+            }
+            checked = pts.len();
+        }
+
+        Ok(pts)
+    }
+
+    fn subdivide<S: Spline>(&self, spline: &S, out: &mut Vec<Point>, t0: f64, t1: f64) {
         let mut t_curr = t0;
         let mut t_step = t1 - t0;
         let t_eps = 1e-3;
@@ -0,0 +1,190 @@
+use crate::{Point, Projection, Spline, Vec2};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Path<S> {
+    segments: Vec<S>,
+    distances: Vec<f64>,
+}
+
+impl<S: Spline> Path<S> {
+    pub fn new(segments: Vec<S>) -> Self {
+        let mut distances = Vec::with_capacity(segments.len() + 1);
+        distances.push(0.);
+        for segment in &segments {
+            let last = *distances.last().unwrap();
+            distances.push(last + segment.arc_length(0., 1.));
+        }
+
+        Path {
+            segments,
+            distances,
+        }
+    }
+
+    pub fn segments(&self) -> &[S] {
+        &self.segments
+    }
+
+    pub fn length(&self) -> f64 {
+        *self.distances.last().unwrap()
+    }
+
+    pub fn distance_at(&self, segment: usize, t: f64) -> Option<f64> {
+        let spline = self.segments.get(segment)?;
+        Some(self.distances[segment] + spline.arc_length(0., t))
+    }
+
+    // None when the path has no segments
+    pub fn locate(&self, distance: f64) -> Option<(usize, f64)> {
+        let last = self.segments.len().checked_sub(1)?;
+        let distance = distance.max(0.).min(self.length());
+        let segment = self
+            .distances
+            .partition_point(|start| *start <= distance)
+            .saturating_sub(1)
+            .min(last);
+
+        let t = self.segments[segment].t_at_distance(distance - self.distances[segment]);
+        Some((segment, t))
+    }
+
+    pub fn point_at_distance(&self, distance: f64) -> Option<Point> {
+        let (segment, t) = self.locate(distance)?;
+        Some(self.segments[segment].point_at(t))
+    }
+
+    // None when the path has no segments; a hint past the last segment is
+    // ignored and the whole path is searched
+    pub fn project(&self, position: Vec2, hint: Option<(usize, f64)>) -> Option<Projection> {
+        let hint = hint.filter(|&(segment, _)| segment < self.segments.len());
+        let candidates: Vec<Projection> = match hint {
+            // stay near the previous projection so self-intersecting paths
+            // don't jump laps
+            Some((segment, t)) => {
+                let mut candidates = vec![self.project_segment(segment, position, Some(t))];
+                if segment > 0 && t < 0.5 {
+                    candidates.push(self.project_segment(segment - 1, position, Some(1.)));
+                }
+                if segment + 1 < self.segments.len() && t > 0.5 {
+                    candidates.push(self.project_segment(segment + 1, position, Some(0.)));
+                }
+                candidates
+            }
+            None => (0..self.segments.len())
+                .map(|segment| self.project_segment(segment, position, None))
+                .collect(),
+        };
+
+        candidates
+            .into_iter()
+            .fold(None, |best: Option<Projection>, candidate| match best {
+                Some(best) if best.distance <= candidate.distance => Some(best),
+                _ => Some(candidate),
+            })
+    }
+
+    fn project_segment(&self, segment: usize, position: Vec2, hint: Option<f64>) -> Projection {
+        Projection {
+            segment,
+            ..self.segments[segment].project(position, hint)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hermites, Clothoid, Hermite, Vector, Waypoint};
+    use std::f64::consts::PI;
+
+    const EPS: f64 = 1e-6;
+
+    // two straight segments along the x axis, (0, 0) -> (3, 0) -> (5, 0)
+    fn line() -> Path<Hermite> {
+        let wps = [
+            Waypoint::new(0., 0., 1., 0., 0., 0.),
+            Waypoint::new(3., 0., 1., 0., 0., 0.),
+            Waypoint::new(5., 0., 1., 0., 0., 0.),
+        ];
+        Path::new(hermites(&wps))
+    }
+
+    #[test]
+    fn straight_segment_arc_length_is_its_chord() {
+        let wps = [
+            Waypoint::new(0., 0., 3., 4., 0., 0.),
+            Waypoint::new(3., 4., 3., 4., 0., 0.),
+        ];
+        let segment = hermites(&wps)[0];
+
+        assert!((segment.arc_length(0., 1.) - 5.).abs() < EPS);
+        let t = segment.t_at_distance(2.);
+        assert!((segment.position(t).norm() - 2.).abs() < EPS);
+    }
+
+    #[test]
+    fn length_and_point_at_distance() {
+        let path = line();
+        assert!((path.length() - 5.).abs() < EPS);
+
+        let at = |distance: f64| path.point_at_distance(distance).unwrap().position;
+        assert!(at(0.).norm() < EPS);
+        assert!((at(4.)[0] - 4.).abs() < EPS);
+        assert!((at(5.)[0] - 5.).abs() < EPS);
+
+        // out of range distances clamp to the ends
+        assert!(at(-1.).norm() < EPS);
+        assert!((at(7.)[0] - 5.).abs() < EPS);
+
+        assert_eq!(path.locate(4.).unwrap().0, 1);
+        assert!((path.distance_at(1, 0.5).unwrap() - 4.).abs() < EPS);
+        assert!(path.distance_at(2, 0.).is_none());
+    }
+
+    #[test]
+    fn empty_path_has_no_points() {
+        let path: Path<Hermite> = Path::new(Vec::new());
+
+        assert_eq!(path.length(), 0.);
+        assert!(path.locate(0.).is_none());
+        assert!(path.point_at_distance(0.).is_none());
+    }
+
+    #[test]
+    fn projects_onto_a_circle() {
+        // unit circle around (0, 1), split into two half circles
+        let top = Clothoid::new([0., 2.], PI, 1., 0., PI);
+        let bottom = Clothoid::new([0., 0.], 0., 1., 0., PI);
+        let path = Path::new(vec![bottom, top]);
+
+        let projection = path.project([3., 1.], None).unwrap();
+        assert_eq!(projection.segment, 0);
+        assert!((projection.t - 0.5).abs() < EPS);
+        assert!((projection.distance - 2.).abs() < EPS);
+        // outside a left turn is to the right of the path
+        assert!((projection.cross_track + 2.).abs() < EPS);
+
+        let projection = path.project([-0.5, 1.], None).unwrap();
+        assert_eq!(projection.segment, 1);
+        assert!((projection.t - 0.5).abs() < EPS);
+        assert!((projection.cross_track - 0.5).abs() < EPS);
+    }
+
+    #[test]
+    fn projection_hints_stay_local() {
+        let path = line();
+
+        let hinted = path.project([2.5, 1.], Some((0, 0.8))).unwrap();
+        assert_eq!(hinted.segment, 0);
+        assert!((hinted.distance - 1.).abs() < EPS);
+
+        // a hint past the last segment is ignored
+        let stale = path.project([4., -1.], Some((5, 0.5))).unwrap();
+        assert_eq!(stale.segment, 1);
+        assert!((stale.distance - 1.).abs() < EPS);
+
+        let empty: Path<Hermite> = Path::new(Vec::new());
+        assert!(empty.project([0., 0.], None).is_none());
+    }
+}
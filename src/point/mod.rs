@@ -7,15 +7,50 @@ pub struct Point {
     pub velocity: Vec2,
     pub acceleration: Vec2,
     pub jerk: Vec2,
+    // driven backwards: the robot faces against its velocity
+    #[serde(default)]
+    pub reversed: bool,
 }
 
 impl Point {
     pub fn heading(&self) -> Vec2 {
-        self.velocity.scale(1. / self.velocity.norm())
+        let heading = self.velocity.unit();
+        if self.reversed {
+            heading.scale(-1.)
+        } else {
+            heading
+        }
     }
 
+    // None while the point is stationary
+    pub fn try_heading(&self) -> Option<Vec2> {
+        let heading = self.velocity.try_unit()?;
+        if self.reversed {
+            Some(heading.scale(-1.))
+        } else {
+            Some(heading)
+        }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        [self.position, self.velocity, self.acceleration, self.jerk]
+            .iter()
+            .all(|v| v.x().is_finite() && v.y().is_finite())
+    }
+
+    pub fn is_stationary(&self) -> bool {
+        self.velocity.norm() == 0.
+    }
+
+    // relative to the robot, so it changes sign when driving backwards
     pub fn curvature(&self) -> f64 {
-        (self.velocity.x() * self.acceleration.y() - self.velocity.y() * self.acceleration.x())
-            / self.velocity.norm().powi(3)
+        let curvature = (self.velocity.x() * self.acceleration.y()
+            - self.velocity.y() * self.acceleration.x())
+            / self.velocity.norm().powi(3);
+        if self.reversed {
+            -curvature
+        } else {
+            curvature
+        }
     }
 }
@@ -0,0 +1,238 @@
+use crate::{
+    hermite, CubicHermite, FromWaypoints, Hermite, HermiteOptions, Spline, Vec2, Vector, Waypoint,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CubicBezier {
+    pub control: [Vec2; 4],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuinticBezier {
+    pub control: [Vec2; 6],
+}
+
+impl CubicBezier {
+    pub fn new(control: [Vec2; 4]) -> Self {
+        CubicBezier { control }
+    }
+}
+
+impl QuinticBezier {
+    pub fn new(control: [Vec2; 6]) -> Self {
+        QuinticBezier { control }
+    }
+}
+
+impl Spline for CubicBezier {
+    fn position(&self, t: f64) -> Vec2 {
+        derivative_at(&self.control, 0, t)
+    }
+
+    fn velocity(&self, t: f64) -> Vec2 {
+        derivative_at(&self.control, 1, t)
+    }
+
+    fn acceleration(&self, t: f64) -> Vec2 {
+        derivative_at(&self.control, 2, t)
+    }
+
+    fn jerk(&self, t: f64) -> Vec2 {
+        derivative_at(&self.control, 3, t)
+    }
+}
+
+impl Spline for QuinticBezier {
+    fn position(&self, t: f64) -> Vec2 {
+        derivative_at(&self.control, 0, t)
+    }
+
+    fn velocity(&self, t: f64) -> Vec2 {
+        derivative_at(&self.control, 1, t)
+    }
+
+    fn acceleration(&self, t: f64) -> Vec2 {
+        derivative_at(&self.control, 2, t)
+    }
+
+    fn jerk(&self, t: f64) -> Vec2 {
+        derivative_at(&self.control, 3, t)
+    }
+}
+
+impl FromWaypoints for QuinticBezier {
+    fn from_wps(start: &Waypoint, end: &Waypoint) -> QuinticBezier {
+        hermite(start, end, &HermiteOptions::default()).into()
+    }
+}
+
+impl FromWaypoints for CubicBezier {
+    fn from_wps(start: &Waypoint, end: &Waypoint) -> CubicBezier {
+        CubicHermite::from_wps(start, end).into()
+    }
+}
+
+// Hermite and CubicHermite store power-basis coefficients, highest power first
+
+impl From<Hermite> for QuinticBezier {
+    fn from(h: Hermite) -> Self {
+        let mut control = [[0.; 2]; 6];
+        control.copy_from_slice(&power_to_bernstein(&h));
+        QuinticBezier { control }
+    }
+}
+
+impl From<QuinticBezier> for Hermite {
+    fn from(b: QuinticBezier) -> Self {
+        let mut coeffs = [[0.; 2]; 6];
+        coeffs.copy_from_slice(&bernstein_to_power(&b.control));
+        coeffs
+    }
+}
+
+impl From<CubicHermite> for CubicBezier {
+    fn from(h: CubicHermite) -> Self {
+        let mut control = [[0.; 2]; 4];
+        control.copy_from_slice(&power_to_bernstein(&h));
+        CubicBezier { control }
+    }
+}
+
+impl From<CubicBezier> for CubicHermite {
+    fn from(b: CubicBezier) -> Self {
+        let mut coeffs = [[0.; 2]; 4];
+        coeffs.copy_from_slice(&bernstein_to_power(&b.control));
+        coeffs
+    }
+}
+
+pub(crate) fn de_casteljau(points: &[Vec2], t: f64) -> Vec2 {
+    let mut points = points.to_vec();
+    for n in (1..points.len()).rev() {
+        for i in 0..n {
+            points[i] = points[i].scale(1. - t).add(points[i + 1].scale(t));
+        }
+    }
+
+    points.first().copied().unwrap_or([0., 0.])
+}
+
+pub(crate) fn hodograph(points: &[Vec2]) -> Vec<Vec2> {
+    let degree = points.len() as f64 - 1.;
+    points
+        .iter()
+        .zip(points.iter().skip(1))
+        .map(|(p0, p1)| p1.add(p0.scale(-1.)).scale(degree))
+        .collect()
+}
+
+fn derivative_at(points: &[Vec2], order: usize, t: f64) -> Vec2 {
+    let points = (0..order).fold(points.to_vec(), |points, _| hodograph(&points));
+    de_casteljau(&points, t)
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1., |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+fn power_to_bernstein(coeffs: &[Vec2]) -> Vec<Vec2> {
+    let n = coeffs.len() - 1;
+    let a = |j: usize| coeffs[n - j];
+
+    (0..=n)
+        .map(|i| {
+            (0..=i).fold([0., 0.], |acc, j| {
+                acc.add(a(j).scale(binomial(i, j) / binomial(n, j)))
+            })
+        })
+        .collect()
+}
+
+fn bernstein_to_power(control: &[Vec2]) -> Vec<Vec2> {
+    let n = control.len() - 1;
+
+    (0..=n)
+        .rev()
+        .map(|j| {
+            (0..=j)
+                .fold([0., 0.], |acc, i| {
+                    let sign = if (j - i) % 2 == 0 { 1. } else { -1. };
+                    acc.add(control[i].scale(sign * binomial(j, i)))
+                })
+                .scale(binomial(n, j))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic_hermite;
+
+    const EPS: f64 = 1e-9;
+
+    fn near(a: Vec2, b: Vec2) -> bool {
+        (a[0] - b[0]).abs() < EPS && (a[1] - b[1]).abs() < EPS
+    }
+
+    fn samples() -> impl Iterator<Item = f64> {
+        (0..=10).map(|i| i as f64 / 10.)
+    }
+
+    #[test]
+    fn quintic_round_trip() {
+        let start = Waypoint::new(0., 0., 2., 1., 0.5, 0.);
+        let end = Waypoint::new(3., 1., 1., -2., -0.3, 0.);
+        let h = hermite(&start, &end, &HermiteOptions::default());
+        let b: QuinticBezier = h.into();
+
+        for t in samples() {
+            assert!(near(b.position(t), h.position(t)));
+            assert!(near(b.velocity(t), h.velocity(t)));
+            assert!(near(b.acceleration(t), h.acceleration(t)));
+        }
+
+        let back: Hermite = b.into();
+        for (a, b) in back.iter().zip(h.iter()) {
+            assert!(near(*a, *b));
+        }
+    }
+
+    #[test]
+    fn cubic_round_trip() {
+        let h = cubic_hermite([0., 0.], [2., 1.], [3., 1.], [1., -2.]);
+        let b: CubicBezier = h.into();
+
+        assert!(near(b.control[0], [0., 0.]));
+        assert!(near(b.control[3], [3., 1.]));
+        for t in samples() {
+            assert!(near(b.position(t), h.position(t)));
+            assert!(near(b.velocity(t), h.velocity(t)));
+        }
+
+        let back: CubicHermite = b.into();
+        for (a, b) in back.iter().zip(h.iter()) {
+            assert!(near(*a, *b));
+        }
+    }
+
+    #[test]
+    fn from_waypoints_matches_hermite() {
+        let start = Waypoint::new(0., 0., 1., 0., 0., 0.);
+        let end = Waypoint::new(2., 2., 0., 1., 0., 0.);
+        let cubic = CubicBezier::from_wps(&start, &end);
+        let quintic = QuinticBezier::from_wps(&start, &end);
+
+        for t in samples() {
+            assert!(near(
+                cubic.position(t),
+                CubicHermite::from_wps(&start, &end).position(t)
+            ));
+            assert!(near(
+                quintic.position(t),
+                Hermite::from_wps(&start, &end).position(t)
+            ));
+        }
+    }
+}
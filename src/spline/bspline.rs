@@ -0,0 +1,332 @@
+use crate::{Spline, Vec2, Vector, WayfinderError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BSpline {
+    degree: usize,
+    knots: Vec<f64>,
+    control: Vec<Vec2>,
+}
+
+impl BSpline {
+    pub fn new(degree: usize, knots: Vec<f64>, control: Vec<Vec2>) -> Result<Self, WayfinderError> {
+        if degree == 0 || control.len() <= degree {
+            return Err(WayfinderError::NotEnoughControlPoints {
+                degree,
+                count: control.len(),
+            });
+        }
+        if knots.len() != control.len() + degree + 1
+            || knots.iter().zip(knots.iter().skip(1)).any(|(a, b)| a > b)
+            || knots[degree] >= knots[control.len()]
+        {
+            return Err(WayfinderError::InvalidKnots);
+        }
+
+        Ok(BSpline {
+            degree,
+            knots,
+            control,
+        })
+    }
+
+    // clamped, uniformly spaced knots so the curve starts and ends on the
+    // first and last control points
+    pub fn uniform(degree: usize, control: Vec<Vec2>) -> Result<Self, WayfinderError> {
+        let spans = control.len().saturating_sub(degree);
+        let knots = (0..control.len() + degree + 1)
+            .map(|i| i.saturating_sub(degree).min(spans) as f64)
+            .collect();
+
+        BSpline::new(degree, knots, control)
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    pub fn knots(&self) -> &[f64] {
+        &self.knots
+    }
+
+    pub fn control(&self) -> &[Vec2] {
+        &self.control
+    }
+
+    pub fn domain(&self) -> (f64, f64) {
+        (self.knots[self.degree], self.knots[self.control.len()])
+    }
+
+    pub fn evaluate(&self, u: f64) -> Vec2 {
+        if self.degree == 0 {
+            return self.control[self.span(u)];
+        }
+
+        let k = self.span(u);
+        let p = self.degree;
+        let mut d: Vec<Vec2> = self.control[k - p..=k].to_vec();
+
+        // de Boor's algorithm
+        for r in 1..=p {
+            for j in (r..=p).rev() {
+                let left = self.knots[j + k - p];
+                let right = self.knots[j + 1 + k - r];
+                let alpha = if right > left {
+                    (u - left) / (right - left)
+                } else {
+                    0.
+                };
+                d[j] = d[j - 1].scale(1. - alpha).add(d[j].scale(alpha));
+            }
+        }
+
+        d[p]
+    }
+
+    pub fn derivative(&self) -> BSpline {
+        let p = self.degree;
+        let control = (0..self.control.len() - 1)
+            .map(|i| {
+                let span = self.knots[i + p + 1] - self.knots[i + 1];
+                if span > 0. {
+                    self.control[i + 1]
+                        .add(self.control[i].scale(-1.))
+                        .scale(p as f64 / span)
+                } else {
+                    [0., 0.]
+                }
+            })
+            .collect();
+
+        BSpline {
+            degree: p - 1,
+            knots: self.knots[1..self.knots.len() - 1].to_vec(),
+            control,
+        }
+    }
+
+    // Boehm's algorithm
+    pub fn insert_knot(&self, u: f64) -> BSpline {
+        let p = self.degree;
+        let k = self.span(u);
+
+        let mut control = Vec::with_capacity(self.control.len() + 1);
+        control.extend_from_slice(&self.control[..=k - p]);
+        for i in k - p + 1..=k {
+            let left = self.knots[i];
+            let right = self.knots[i + p];
+            let alpha = if right > left {
+                (u - left) / (right - left)
+            } else {
+                0.
+            };
+            control.push(
+                self.control[i - 1]
+                    .scale(1. - alpha)
+                    .add(self.control[i].scale(alpha)),
+            );
+        }
+        control.extend_from_slice(&self.control[k..]);
+
+        let mut knots = self.knots.clone();
+        knots.insert(k + 1, u);
+
+        BSpline {
+            degree: p,
+            knots,
+            control,
+        }
+    }
+
+    // one Bezier-form BSpline per non-empty knot span, each reparameterized
+    // over [0, 1]
+    pub fn spans(&self) -> Vec<BSpline> {
+        let p = self.degree;
+        let (lo, hi) = self.domain();
+
+        let mut breakpoints: Vec<f64> = vec![lo];
+        for u in self.knots.iter().filter(|u| **u > lo && **u <= hi) {
+            if breakpoints.last() != Some(u) {
+                breakpoints.push(*u);
+            }
+        }
+
+        // raise every breakpoint to multiplicity p so each span's control
+        // points become its Bezier control points
+        let mut refined = self.clone();
+        for u in &breakpoints {
+            let multiplicity = refined.knots.iter().filter(|k| *k == u).count();
+            for _ in multiplicity..p {
+                refined = refined.insert_knot(*u);
+            }
+        }
+
+        let bezier_knots: Vec<f64> = (0..2 * (p + 1))
+            .map(|i| if i <= p { 0. } else { 1. })
+            .collect();
+        // new() only accepts sorted knots and lo is knots[p], so at least
+        // p + 1 knots are <= lo and the last of them starts the first span
+        let first = refined.knots.iter().filter(|u| **u <= lo).count() - 1 - p;
+
+        (0..breakpoints.len() - 1)
+            .map(|segment| BSpline {
+                degree: p,
+                knots: bezier_knots.clone(),
+                control: refined.control[first + segment * p..=first + (segment + 1) * p].to_vec(),
+            })
+            .collect()
+    }
+
+    fn span(&self, u: f64) -> usize {
+        let (lo, hi) = self.domain();
+        let u = u.max(lo).min(hi);
+        let last = self.control.len() - 1;
+
+        if u >= hi {
+            // the last non-empty span
+            return (self.degree..=last)
+                .rev()
+                .find(|i| self.knots[*i] < self.knots[i + 1])
+                .unwrap_or(last);
+        }
+
+        (self.degree..=last)
+            .find(|i| u >= self.knots[*i] && u < self.knots[i + 1])
+            .unwrap_or(last)
+    }
+
+    fn nth_derivative_at(&self, order: usize, t: f64) -> Vec2 {
+        let (lo, hi) = self.domain();
+        let u = lo + t * (hi - lo);
+
+        if order > self.degree {
+            return [0., 0.];
+        }
+
+        let derivative = (0..order).fold(self.clone(), |spline, _| spline.derivative());
+        derivative.evaluate(u).scale((hi - lo).powi(order as i32))
+    }
+}
+
+// Spline's t in [0, 1] is mapped linearly onto the knot domain
+impl Spline for BSpline {
+    fn position(&self, t: f64) -> Vec2 {
+        self.nth_derivative_at(0, t)
+    }
+
+    fn velocity(&self, t: f64) -> Vec2 {
+        self.nth_derivative_at(1, t)
+    }
+
+    fn acceleration(&self, t: f64) -> Vec2 {
+        self.nth_derivative_at(2, t)
+    }
+
+    fn jerk(&self, t: f64) -> Vec2 {
+        self.nth_derivative_at(3, t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPS: f64 = 1e-9;
+
+    fn near(a: Vec2, b: Vec2, eps: f64) -> bool {
+        (a[0] - b[0]).abs() < eps && (a[1] - b[1]).abs() < eps
+    }
+
+    fn cubic() -> BSpline {
+        BSpline::new(
+            3,
+            vec![0., 0., 0., 0., 1., 2.5, 3., 3., 3., 3.],
+            vec![[0., 0.], [1., 2.], [2., -1.], [4., 0.], [5., 3.], [7., 1.]],
+        )
+        .unwrap()
+    }
+
+    fn samples() -> impl Iterator<Item = f64> {
+        (0..=20).map(|i| i as f64 / 20.)
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(
+            BSpline::uniform(3, vec![[0., 0.], [1., 0.], [2., 0.]]),
+            Err(WayfinderError::NotEnoughControlPoints {
+                degree: 3,
+                count: 3
+            })
+        );
+        assert_eq!(
+            BSpline::new(1, vec![0., 2., 1., 3.], vec![[0., 0.], [1., 0.]]),
+            Err(WayfinderError::InvalidKnots)
+        );
+        assert_eq!(
+            BSpline::new(1, vec![0., 1., 1.], vec![[0., 0.], [1., 0.]]),
+            Err(WayfinderError::InvalidKnots)
+        );
+    }
+
+    #[test]
+    fn clamped_ends_hit_control_points() {
+        let spline = cubic();
+        assert!(near(spline.position(0.), [0., 0.], EPS));
+        assert!(near(spline.position(1.), [7., 1.], EPS));
+    }
+
+    #[test]
+    fn insert_knot_preserves_the_curve() {
+        let spline = cubic();
+        let refined = spline.insert_knot(1.7).insert_knot(1.7).insert_knot(0.4);
+
+        assert_eq!(refined.control().len(), spline.control().len() + 3);
+        for t in samples() {
+            assert!(near(refined.position(t), spline.position(t), EPS));
+        }
+    }
+
+    #[test]
+    fn derivatives_match_finite_differences() {
+        let spline = cubic();
+        let h = 1e-6;
+
+        // stay away from the interior knots, where the acceleration of a
+        // cubic is only continuous and the jerk jumps
+        for t in [0.1, 0.2, 0.45, 0.6, 0.9].iter() {
+            let (before, after) = (t - h, t + h);
+            let velocity = spline
+                .position(after)
+                .add(spline.position(before).scale(-1.))
+                .scale(0.5 / h);
+            let acceleration = spline
+                .velocity(after)
+                .add(spline.velocity(before).scale(-1.))
+                .scale(0.5 / h);
+            let jerk = spline
+                .acceleration(after)
+                .add(spline.acceleration(before).scale(-1.))
+                .scale(0.5 / h);
+
+            assert!(near(spline.velocity(*t), velocity, 1e-5));
+            assert!(near(spline.acceleration(*t), acceleration, 1e-4));
+            assert!(near(spline.jerk(*t), jerk, 1e-3));
+        }
+    }
+
+    #[test]
+    fn spans_reproduce_the_curve() {
+        let spline = cubic();
+        let spans = spline.spans();
+        let breakpoints = [0., 1., 2.5, 3.];
+
+        assert_eq!(spans.len(), 3);
+        for (span, range) in spans.iter().zip(breakpoints.windows(2)) {
+            for t in samples() {
+                let u = range[0] + t * (range[1] - range[0]);
+                assert!(near(span.position(t), spline.evaluate(u), EPS));
+            }
+        }
+    }
+}
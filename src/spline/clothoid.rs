@@ -0,0 +1,264 @@
+use crate::{
+    adaptive_simpson, fresnel, splines, try_splines, Coordinate, FromWaypoints, Rotation, Spline,
+    Translation, Vec2, Vector, WayfinderError, Waypoint,
+};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+const FIT_TOL: f64 = 1e-12;
+const MAX_FIT_ITERS: usize = 30;
+const INTEGRAL_TOL: f64 = 1e-13;
+// below this |sharpness| * length^2 the Fresnel form loses precision,
+// so the heading integral is evaluated numerically instead
+const SHARPNESS_EPS: f64 = 1e-4;
+
+// Curvature varies linearly with arc length s:
+// kappa(s) = curvature + sharpness * s, for s in [0, length].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Clothoid {
+    pub start: Vec2,
+    pub heading: f64,
+    pub curvature: f64,
+    pub sharpness: f64,
+    pub length: f64,
+}
+
+pub fn clothoids(wps: &[Waypoint]) -> Vec<Clothoid> {
+    splines(wps)
+}
+
+pub fn try_clothoids(wps: &[Waypoint]) -> Result<Vec<Clothoid>, WayfinderError> {
+    try_splines(wps)
+}
+
+impl Clothoid {
+    pub fn new(start: Vec2, heading: f64, curvature: f64, sharpness: f64, length: f64) -> Self {
+        Clothoid {
+            start,
+            heading,
+            curvature,
+            sharpness,
+            length,
+        }
+    }
+
+    // G1 Hermite interpolation after Bertolazzi & Frego, "G1 fitting with
+    // clothoids" (2015): headings are measured relative to the chord and the
+    // unknown quadratic coefficient of the heading is found by Newton's method.
+    // None when the endpoints coincide or the fit does not produce a finite
+    // clothoid.
+    pub fn fit(start: Vec2, start_heading: f64, end: Vec2, end_heading: f64) -> Option<Self> {
+        if !start_heading.is_finite() || !end_heading.is_finite() {
+            return None;
+        }
+        let chord = end.add(start.scale(-1.));
+        let r = chord.norm();
+        let chord_rotation = chord.try_unit()?;
+
+        let phi0 =
+            Translation::rotate_by(Vec2::from_radians(start_heading), chord_rotation.inverse())
+                .as_radians();
+        let phi1 =
+            Translation::rotate_by(Vec2::from_radians(end_heading), chord_rotation.inverse())
+                .as_radians();
+        let delta = phi1 - phi0;
+
+        let mut a = 3. * (phi0 + phi1);
+        for _ in 0..MAX_FIT_ITERS {
+            let g = normalized_integral(2. * a, delta - a, phi0, |_| 1.).y();
+            let dg = normalized_integral(2. * a, delta - a, phi0, |tau| tau * tau - tau).x();
+            let step = g / dg;
+            a -= step;
+            if step.abs() < FIT_TOL {
+                break;
+            }
+        }
+
+        let length = r / normalized_integral(2. * a, delta - a, phi0, |_| 1.).x();
+        if !a.is_finite() || !length.is_finite() || length <= 0. {
+            return None;
+        }
+
+        Some(Clothoid {
+            start,
+            heading: start_heading,
+            curvature: (delta - a) / length,
+            sharpness: 2. * a / (length * length),
+            length,
+        })
+    }
+
+    pub fn heading_at(&self, s: f64) -> f64 {
+        self.heading + self.curvature * s + 0.5 * self.sharpness * s * s
+    }
+
+    pub fn curvature_at(&self, s: f64) -> f64 {
+        self.curvature + self.sharpness * s
+    }
+
+    pub fn position_at(&self, s: f64) -> Vec2 {
+        let c = self.sharpness;
+
+        if c.abs() * self.length * self.length < SHARPNESS_EPS {
+            let offset = adaptive_simpson(
+                |u| Vec2::from_radians(self.heading_at(u)),
+                0.,
+                s,
+                INTEGRAL_TOL,
+            );
+            return self.start.add(offset);
+        }
+
+        // complete the square: theta(u) = c / 2 * (u + kappa / c)^2 + phi,
+        // then substitute w = sqrt(|c| / pi) * (u + kappa / c)
+        let scale = (c.abs() / PI).sqrt();
+        let phi = self.heading - self.curvature * self.curvature / (2. * c);
+        let (c0, s0) = fresnel(scale * self.curvature / c);
+        let (c1, s1) = fresnel(scale * (s + self.curvature / c));
+
+        let offset =
+            Translation::rotate_by([c1 - c0, c.signum() * (s1 - s0)], Vec2::from_radians(phi))
+                .scale(1. / scale);
+        self.start.add(offset)
+    }
+
+    pub fn end(&self) -> Vec2 {
+        self.position_at(self.length)
+    }
+
+    pub fn end_heading(&self) -> f64 {
+        self.heading_at(self.length)
+    }
+}
+
+// integral over [0, 1] of weight(tau) * exp(i * (a / 2 * tau^2 + b * tau + c))
+fn normalized_integral<F: Fn(f64) -> f64>(a: f64, b: f64, c: f64, weight: F) -> Vec2 {
+    adaptive_simpson(
+        |tau| Vec2::from_radians(0.5 * a * tau * tau + b * tau + c).scale(weight(tau)),
+        0.,
+        1.,
+        INTEGRAL_TOL,
+    )
+}
+
+impl Spline for Clothoid {
+    fn position(&self, t: f64) -> Vec2 {
+        self.position_at(t * self.length)
+    }
+
+    fn velocity(&self, t: f64) -> Vec2 {
+        Vec2::from_radians(self.heading_at(t * self.length)).scale(self.length)
+    }
+
+    fn acceleration(&self, t: f64) -> Vec2 {
+        let s = t * self.length;
+        Translation::rotate_by(Vec2::from_radians(self.heading_at(s)), [0., 1.])
+            .scale(self.length * self.length * self.curvature_at(s))
+    }
+
+    fn jerk(&self, t: f64) -> Vec2 {
+        let s = t * self.length;
+        let kappa = self.curvature_at(s);
+        let tangent = Vec2::from_radians(self.heading_at(s));
+
+        Translation::rotate_by(tangent, [0., 1.])
+            .scale(self.sharpness)
+            .add(tangent.scale(-kappa * kappa))
+            .scale(self.length.powi(3))
+    }
+
+    fn curvature(&self, t: f64) -> f64 {
+        self.curvature_at(t * self.length)
+    }
+
+    fn d_curvature(&self, _t: f64) -> f64 {
+        self.sharpness * self.length
+    }
+
+    fn arc_length(&self, t0: f64, t1: f64) -> f64 {
+        (t1 - t0) * self.length
+    }
+
+    fn t_at_distance(&self, distance: f64) -> f64 {
+        if self.length <= 0. {
+            return 0.;
+        }
+        (distance / self.length).clamp(0., 1.)
+    }
+}
+
+impl FromWaypoints for Clothoid {
+    fn from_wps(start: &Waypoint, end: &Waypoint) -> Clothoid {
+        let heading = start.tangent.as_radians();
+
+        // try_clothoids rejects the coincident waypoints that make the fit
+        // fail; unchecked input degrades to a zero-length clothoid
+        Clothoid::fit(start.point, heading, end.point, end.tangent.as_radians())
+            .unwrap_or_else(|| Clothoid::new(start.point, heading, 0., 0., 0.))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPS: f64 = 1e-8;
+
+    fn near(a: f64, b: f64) -> bool {
+        (a - b).abs() < EPS
+    }
+
+    fn angle_between(a: f64, b: f64) -> f64 {
+        Translation::rotate_by(Vec2::from_radians(a), Vec2::from_radians(b).inverse())
+            .as_radians()
+            .abs()
+    }
+
+    #[test]
+    fn fit_reaches_the_end_pose() {
+        let cases = [
+            ([0., 0.], 0., [4., 0.], 0.),
+            ([0., 0.], 0., [2., 2.], PI / 2.),
+            ([1., -1.], 0.3, [-2., 3.], 2.5),
+            ([0., 0.], PI / 2., [3., 0.], -PI / 2.),
+            ([0., 0.], 0.2, [5., 1.], -0.4),
+        ];
+
+        for (start, start_heading, end, end_heading) in cases.iter() {
+            let clothoid = Clothoid::fit(*start, *start_heading, *end, *end_heading).unwrap();
+            let reached = clothoid.end();
+
+            assert!(near(clothoid.position(0.).x(), start.x()));
+            assert!(near(clothoid.position(0.).y(), start.y()));
+            assert!(near(reached.x(), end.x()));
+            assert!(near(reached.y(), end.y()));
+            assert!(angle_between(clothoid.end_heading(), *end_heading) < EPS);
+        }
+    }
+
+    #[test]
+    fn straight_fit_is_a_line() {
+        let clothoid = Clothoid::fit([0., 0.], 0., [4., 0.], 0.).unwrap();
+
+        assert!(near(clothoid.length, 4.));
+        assert!(near(clothoid.curvature, 0.));
+        assert!(near(clothoid.sharpness, 0.));
+    }
+
+    #[test]
+    fn degenerate_fit_is_none() {
+        assert_eq!(Clothoid::fit([1., 1.], 0., [1., 1.], 1.), None);
+        assert_eq!(Clothoid::fit([0., 0.], f64::NAN, [1., 0.], 0.), None);
+        assert_eq!(Clothoid::fit([0., 0.], 0., [f64::INFINITY, 0.], 0.), None);
+    }
+
+    #[test]
+    fn coincident_waypoints_degrade_to_zero_length() {
+        let wp = Waypoint::new(1., 1., 1., 0., 0., 0.);
+        let clothoid = Clothoid::from_wps(&wp, &wp);
+
+        assert_eq!(clothoid.length, 0.);
+        assert_eq!(clothoid.end(), [1., 1.]);
+        assert!(try_clothoids(&[wp.clone(), wp]).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    check_waypoints, splines, try_splines, FromWaypoints, HermiteOptions, Mat2x4, Mat4, MatMul,
+    Spline, TangentScale, Vec2, WayfinderError, Waypoint,
+};
+
+pub type CubicHermite = Mat2x4;
+
+pub fn cubic_hermites(wps: &[Waypoint]) -> Vec<CubicHermite> {
+    splines(wps)
+}
+
+pub fn try_cubic_hermites(wps: &[Waypoint]) -> Result<Vec<CubicHermite>, WayfinderError> {
+    try_splines(wps)
+}
+
+pub fn cubic_hermites_with(wps: &[Waypoint], opts: &HermiteOptions) -> Vec<CubicHermite> {
+    wps.iter()
+        .zip(wps.iter().skip(1))
+        .map(|(start, end)| {
+            let (tangent_0, tangent_1) = opts.tangent_scale.apply(start, end);
+            cubic_hermite(start.point, tangent_0, end.point, tangent_1)
+        })
+        .collect()
+}
+
+pub fn try_cubic_hermites_with(
+    wps: &[Waypoint],
+    opts: &HermiteOptions,
+) -> Result<Vec<CubicHermite>, WayfinderError> {
+    check_waypoints(wps)?;
+    Ok(cubic_hermites_with(wps, opts))
+}
+
+pub fn cubic_hermite(p0: Vec2, m0: Vec2, p1: Vec2, m1: Vec2) -> CubicHermite {
+    [p0, m0, p1, m1].mul(CUBIC_COEFF_MATRIX)
+}
+
+static CUBIC_COEFF_MATRIX: &Mat4 = &[
+    [2.0, 1.0, -2.0, 1.0],
+    [-3.0, -2.0, 3.0, -1.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [1.0, 0.0, 0.0, 0.0],
+];
+
+impl FromWaypoints for CubicHermite {
+    fn from_wps(start: &Waypoint, end: &Waypoint) -> CubicHermite {
+        let (scaled_tangent_0, scaled_tangent_1) = TangentScale::default().apply(start, end);
+
+        cubic_hermite(start.point, scaled_tangent_0, end.point, scaled_tangent_1)
+    }
+}
+
+impl Spline for CubicHermite {
+    fn position(&self, t: f64) -> Vec2 {
+        let basis = &[t * t * t, t * t, t, 1.0];
+
+        self.mul(basis)
+    }
+
+    fn velocity(&self, t: f64) -> Vec2 {
+        let basis = &[3.0 * t * t, 2.0 * t, 1.0, 0.0];
+
+        self.mul(basis)
+    }
+
+    fn acceleration(&self, t: f64) -> Vec2 {
+        let basis = &[6.0 * t, 2.0, 0.0, 0.0];
+
+        self.mul(basis)
+    }
+
+    fn jerk(&self, _: f64) -> Vec2 {
+        let basis = &[6.0, 0.0, 0.0, 0.0];
+
+        self.mul(basis)
+    }
+}
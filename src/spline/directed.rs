@@ -0,0 +1,61 @@
+use crate::{Spline, Vec2};
+use serde::{Deserialize, Serialize};
+
+// Marks a segment as driven forwards or backwards without changing its shape.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Directed<S> {
+    pub spline: S,
+    pub reversed: bool,
+}
+
+impl<S: Spline> Directed<S> {
+    pub fn new(spline: S, reversed: bool) -> Self {
+        Directed { spline, reversed }
+    }
+
+    pub fn forward(spline: S) -> Self {
+        Directed::new(spline, false)
+    }
+
+    pub fn backward(spline: S) -> Self {
+        Directed::new(spline, true)
+    }
+}
+
+impl<S: Spline> Spline for Directed<S> {
+    fn position(&self, t: f64) -> Vec2 {
+        self.spline.position(t)
+    }
+
+    fn velocity(&self, t: f64) -> Vec2 {
+        self.spline.velocity(t)
+    }
+
+    fn acceleration(&self, t: f64) -> Vec2 {
+        self.spline.acceleration(t)
+    }
+
+    fn jerk(&self, t: f64) -> Vec2 {
+        self.spline.jerk(t)
+    }
+
+    fn reversed(&self) -> bool {
+        self.reversed
+    }
+
+    fn curvature(&self, t: f64) -> f64 {
+        self.spline.curvature(t)
+    }
+
+    fn d_curvature(&self, t: f64) -> f64 {
+        self.spline.d_curvature(t)
+    }
+
+    fn arc_length(&self, t0: f64, t1: f64) -> f64 {
+        self.spline.arc_length(t0, t1)
+    }
+
+    fn t_at_distance(&self, distance: f64) -> f64 {
+        self.spline.t_at_distance(distance)
+    }
+}
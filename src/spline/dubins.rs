@@ -0,0 +1,170 @@
+use crate::{chain, well_posed, Coordinate, PathPrimitive, Pose, Vector};
+use std::f64::consts::PI;
+
+const LEFT: f64 = 1.;
+const STRAIGHT: f64 = 0.;
+const RIGHT: f64 = -1.;
+
+fn mod2pi(x: f64) -> f64 {
+    x.rem_euclid(2. * PI)
+}
+
+// Shortest forward-only path with a minimum turning radius, choosing among
+// the six Dubins words (LSL, RSR, LSR, RSL, RLR, LRL). None for a
+// non-positive radius or non-finite poses.
+pub fn dubins(start: &Pose, end: &Pose, radius: f64) -> Option<Vec<PathPrimitive>> {
+    if !well_posed(start, end, radius) {
+        return None;
+    }
+
+    let delta = end.translation().add(start.translation().scale(-1.));
+    let d = delta.norm() / radius;
+    let theta = delta.y().atan2(delta.x());
+    let a = mod2pi(start.heading() - theta);
+    let b = mod2pi(end.heading() - theta);
+
+    let (sa, ca) = a.sin_cos();
+    let (sb, cb) = b.sin_cos();
+    let c_ab = (a - b).cos();
+
+    let mut words: Vec<[(f64, f64); 3]> = Vec::with_capacity(6);
+
+    let p_sq = 2. + d * d - 2. * c_ab + 2. * d * (sa - sb);
+    if p_sq >= 0. {
+        let tmp = (cb - ca).atan2(d + sa - sb);
+        words.push([
+            (LEFT, mod2pi(tmp - a)),
+            (STRAIGHT, p_sq.sqrt()),
+            (LEFT, mod2pi(b - tmp)),
+        ]);
+    }
+
+    let p_sq = 2. + d * d - 2. * c_ab + 2. * d * (sb - sa);
+    if p_sq >= 0. {
+        let tmp = (ca - cb).atan2(d - sa + sb);
+        words.push([
+            (RIGHT, mod2pi(a - tmp)),
+            (STRAIGHT, p_sq.sqrt()),
+            (RIGHT, mod2pi(tmp - b)),
+        ]);
+    }
+
+    let p_sq = -2. + d * d + 2. * c_ab + 2. * d * (sa + sb);
+    if p_sq >= 0. {
+        let p = p_sq.sqrt();
+        let tmp = (-ca - cb).atan2(d + sa + sb) - (-2f64).atan2(p);
+        words.push([
+            (LEFT, mod2pi(tmp - a)),
+            (STRAIGHT, p),
+            (RIGHT, mod2pi(tmp - b)),
+        ]);
+    }
+
+    let p_sq = -2. + d * d + 2. * c_ab - 2. * d * (sa + sb);
+    if p_sq >= 0. {
+        let p = p_sq.sqrt();
+        let tmp = (ca + cb).atan2(d - sa - sb) - 2f64.atan2(p);
+        words.push([
+            (RIGHT, mod2pi(a - tmp)),
+            (STRAIGHT, p),
+            (LEFT, mod2pi(b - tmp)),
+        ]);
+    }
+
+    let tmp = (6. - d * d + 2. * c_ab + 2. * d * (sa - sb)) / 8.;
+    if tmp.abs() <= 1. {
+        let phi = (ca - cb).atan2(d - sa + sb);
+        let p = mod2pi(2. * PI - tmp.acos());
+        let t = mod2pi(a - phi + p / 2.);
+        words.push([(RIGHT, t), (LEFT, p), (RIGHT, mod2pi(a - b - t + p))]);
+    }
+
+    let tmp = (6. - d * d + 2. * c_ab + 2. * d * (sb - sa)) / 8.;
+    if tmp.abs() <= 1. {
+        let phi = (ca - cb).atan2(d + sa - sb);
+        let p = mod2pi(2. * PI - tmp.acos());
+        let t = mod2pi(-a - phi + p / 2.);
+        words.push([(LEFT, t), (RIGHT, p), (LEFT, mod2pi(b - a - t + p))]);
+    }
+
+    let cost = |word: &[(f64, f64); 3]| word.iter().map(|(_, length)| length).sum::<f64>();
+
+    words
+        .iter()
+        .min_by(|w0, w1| cost(w0).total_cmp(&cost(w1)))
+        .map(|word| chain(start, radius, word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{primitives_length, Gear, Rotation, Translation};
+    use proptest::prelude::*;
+
+    const EPS: f64 = 1e-6;
+
+    fn pose(x: f64, y: f64, theta: f64) -> Pose {
+        Pose::new([x, y], [theta.cos(), theta.sin()])
+    }
+
+    fn assert_reaches(primitives: &[PathPrimitive], start: &Pose, end: &Pose) {
+        let mut pose = *start;
+        for primitive in primitives {
+            assert!(
+                primitive
+                    .start
+                    .translation()
+                    .add(pose.translation().scale(-1.))
+                    .norm()
+                    < EPS
+            );
+            pose = primitive.end_pose();
+        }
+
+        assert!(pose.translation().add(end.translation().scale(-1.)).norm() < EPS);
+        assert!(
+            Translation::rotate_by(pose.rotation(), end.rotation().inverse())
+                .as_radians()
+                .abs()
+                < EPS
+        );
+    }
+
+    prop_compose! {
+        fn arb_pose()(x in -10.0..10.0, y in -10.0..10.0, theta in -PI..PI) -> Pose {
+            pose(x, y, theta)
+        }
+    }
+
+    #[test]
+    fn straight_ahead_is_a_line() {
+        let (start, end) = (pose(0., 0., 0.), pose(5., 0., 0.));
+        let primitives = dubins(&start, &end, 1.).unwrap();
+
+        assert_eq!(primitives.len(), 1);
+        assert_eq!(primitives[0].curvature, 0.);
+        assert!((primitives_length(&primitives) - 5.).abs() < EPS);
+    }
+
+    #[test]
+    fn ill_posed_input_is_none() {
+        let (start, end) = (pose(0., 0., 0.), pose(5., 0., 0.));
+
+        assert!(dubins(&start, &end, 0.).is_none());
+        assert!(dubins(&start, &end, -1.).is_none());
+        assert!(dubins(&start, &end, f64::NAN).is_none());
+        assert!(dubins(&pose(f64::NAN, 0., 0.), &end, 1.).is_none());
+        assert!(dubins(&start, &pose(0., 0., f64::INFINITY), 1.).is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn reaches_the_goal_driving_forward(start in arb_pose(), end in arb_pose(), radius in 0.2..3.0) {
+            let primitives = dubins(&start, &end, radius).unwrap();
+
+            assert_reaches(&primitives, &start, &end);
+            prop_assert!(primitives.iter().all(|p| p.gear == Gear::Forward));
+            prop_assert!(primitives.iter().all(|p| p.curvature.abs() <= 1. / radius + EPS));
+        }
+    }
+}
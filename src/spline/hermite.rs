@@ -1,14 +1,33 @@
-use crate::{Coordinate, Mat2x6, Mat6, MatMul, Spline, Vec2, Vector, Waypoint};
+use crate::{
+    check_waypoints, integrate, splines, try_splines, Coordinate, FromWaypoints, HermiteOptions,
+    Mat2x6, Mat6, MatMul, Matrix, Spline, Vec2, Vec4, Vec6, Vector, WayfinderError, Waypoint,
+};
 
 pub type Hermite = Mat2x6;
 
 pub fn hermites(wps: &[Waypoint]) -> Vec<Hermite> {
+    splines(wps)
+}
+
+pub fn try_hermites(wps: &[Waypoint]) -> Result<Vec<Hermite>, WayfinderError> {
+    try_splines(wps)
+}
+
+pub fn hermites_with(wps: &[Waypoint], opts: &HermiteOptions) -> Vec<Hermite> {
     wps.iter()
         .zip(wps.iter().skip(1))
-        .map(|(start, end)| Hermite::from_wps(start, end))
+        .map(|(start, end)| hermite(start, end, opts))
         .collect()
 }
 
+pub fn try_hermites_with(
+    wps: &[Waypoint],
+    opts: &HermiteOptions,
+) -> Result<Vec<Hermite>, WayfinderError> {
+    check_waypoints(wps)?;
+    Ok(hermites_with(wps, opts))
+}
+
 static COEFF_MATRIX: &Mat6 = &[
     [-6.0, -3.0, -0.5, 6.0, -3.0, 0.5],
     [15.0, 8.0, 1.5, -15.0, 7.0, -1.0],
@@ -18,79 +37,179 @@ static COEFF_MATRIX: &Mat6 = &[
     [1.0, 0.0, 0.0, 0.0, 0.0, 0.0],
 ];
 
-impl Spline<Hermite> for Hermite {
+pub fn hermite(start: &Waypoint, end: &Waypoint, opts: &HermiteOptions) -> Hermite {
+    let (scaled_tangent_0, scaled_tangent_1) = opts.tangent_scale.apply(start, end);
+
+    let coords = [
+        start.point,
+        scaled_tangent_0,
+        start.curvature,
+        end.point,
+        scaled_tangent_1,
+        end.curvature,
+    ];
+
+    coords.mul(COEFF_MATRIX)
+}
+
+impl FromWaypoints for Hermite {
     fn from_wps(start: &Waypoint, end: &Waypoint) -> Hermite {
-        let dist = 1.2 * end.point.add(start.point.scale(-1.)).norm();
-        let scaled_tangent_0 = start.tangent.unit().scale(dist);
-        let scaled_tangent_1 = end.tangent.unit().scale(dist);
-
-        let coords = [
-            start.point,
-            scaled_tangent_0,
-            start.curvature,
-            end.point,
-            scaled_tangent_1,
-            end.curvature,
-        ];
-
-        coords.mul(COEFF_MATRIX)
+        hermite(start, end, &HermiteOptions::default())
     }
+}
 
-    fn position(&self, t: f64) -> Vec2 {
-        let basis = &[t * t * t * t * t, t * t * t * t, t * t * t, t * t, t, 1.0];
+fn position_basis(t: f64) -> Vec6 {
+    [t * t * t * t * t, t * t * t * t, t * t * t, t * t, t, 1.0]
+}
+
+fn velocity_basis(t: f64) -> Vec6 {
+    [
+        5.0 * t * t * t * t,
+        4.0 * t * t * t,
+        3.0 * t * t,
+        2.0 * t,
+        1.0,
+        0.0,
+    ]
+}
+
+fn acceleration_basis(t: f64) -> Vec6 {
+    [20.0 * t * t * t, 12.0 * t * t, 6.0 * t, 2.0, 0.0, 0.0]
+}
+
+fn jerk_basis(t: f64) -> Vec6 {
+    [60.0 * t * t, 24.0 * t, 6.0, 0.0, 0.0, 0.0]
+}
 
-        self.mul(basis)
+impl Spline for Hermite {
+    fn position(&self, t: f64) -> Vec2 {
+        self.mul(&position_basis(t))
     }
 
     fn velocity(&self, t: f64) -> Vec2 {
-        let basis = &[
-            5.0 * t * t * t * t,
-            4.0 * t * t * t,
-            3.0 * t * t,
-            2.0 * t,
-            1.0,
-            0.0,
-        ];
-
-        self.mul(basis)
+        self.mul(&velocity_basis(t))
     }
 
     fn acceleration(&self, t: f64) -> Vec2 {
-        let basis = &[20.0 * t * t * t, 12.0 * t * t, 6.0 * t, 2.0, 0.0, 0.0];
-
-        self.mul(basis)
+        self.mul(&acceleration_basis(t))
     }
 
     fn jerk(&self, t: f64) -> Vec2 {
-        let basis = &[60.0 * t * t, 24.0 * t, 6.0, 0.0, 0.0, 0.0];
-
-        self.mul(basis)
+        self.mul(&jerk_basis(t))
     }
+}
 
-    fn curvature(&self, t: f64) -> f64 {
-        let vel = self.velocity(t);
-        let acc = self.acceleration(t);
-
-        (vel.x() * acc.y() - vel.y() * acc.x()) / vel.norm().powi(3)
-    }
+// Gradients are taken with respect to
+// [start.curvature.x, start.curvature.y, end.curvature.x, end.curvature.y].
+// Each Hermite is linear in its waypoint data, so perturbing one curvature
+// component moves the derivatives along a single column of COEFF_MATRIX.
+pub trait CurvatureGradient {
+    fn d_curvature_gradient(&self, t: f64) -> Vec4;
+    fn integral_d_curvature_d_t_squared_gradient(&self, tol: f64) -> Vec4;
+}
 
-    fn d_curvature(&self, t: f64) -> f64 {
+impl CurvatureGradient for Hermite {
+    fn d_curvature_gradient(&self, t: f64) -> Vec4 {
         let vel = self.velocity(t);
         let acc = self.acceleration(t);
         let jerk = self.jerk(t);
 
-        let top = (vel.x() * jerk.y() - jerk.x() * vel.y()) * vel.norm_squared()
-            - 3.0
-                * (vel.x() * acc.y() - acc.x() * vel.y())
-                * (vel.x() * acc.x() + vel.y() * acc.y());
-        top / vel.norm().powi(5)
+        let (vel_basis, acc_basis, jerk_basis) =
+            (velocity_basis(t), acceleration_basis(t), jerk_basis(t));
+
+        let mut grad = [0.; 4];
+        for (i, coord) in [2, 5].iter().enumerate() {
+            let weights = COEFF_MATRIX.row(*coord);
+            let (dv, da, dj) = (
+                weights.dot(&vel_basis),
+                weights.dot(&acc_basis),
+                weights.dot(&jerk_basis),
+            );
+
+            grad[2 * i] = d_curvature_directional(vel, acc, jerk, [dv, 0.], [da, 0.], [dj, 0.]);
+            grad[2 * i + 1] = d_curvature_directional(vel, acc, jerk, [0., dv], [0., da], [0., dj]);
+        }
+
+        grad
     }
 
-    fn integral_d_curvature_d_t_squared(&self, samples: u64) -> f64 {
-        (0..samples).fold(0.0, |acc, it| {
-            let left = self.d_curvature(it as f64 / samples as f64);
-            let right = self.d_curvature((it + 1) as f64 / samples as f64);
-            acc + (left.powi(2) + right.powi(2)) / 2.0
-        }) / (samples as f64)
+    fn integral_d_curvature_d_t_squared_gradient(&self, tol: f64) -> Vec4 {
+        integrate(
+            self,
+            |h, t| h.d_curvature_gradient(t).scale(2. * h.d_curvature(t)),
+            tol,
+        )
+    }
+}
+
+fn cross(a: Vec2, b: Vec2) -> f64 {
+    a.x() * b.y() - a.y() * b.x()
+}
+
+// derivative of Spline::d_curvature along a perturbation (dvel, dacc, djerk)
+fn d_curvature_directional(
+    vel: Vec2,
+    acc: Vec2,
+    jerk: Vec2,
+    dvel: Vec2,
+    dacc: Vec2,
+    djerk: Vec2,
+) -> f64 {
+    let norm_squared = vel.norm_squared();
+    let vel_cross_jerk = cross(vel, jerk);
+    let vel_cross_acc = cross(vel, acc);
+    let vel_dot_acc = vel.dot(&acc);
+
+    let top = vel_cross_jerk * norm_squared - 3.0 * vel_cross_acc * vel_dot_acc;
+
+    let d_norm_squared = 2. * vel.dot(&dvel);
+    let d_vel_cross_jerk = cross(dvel, jerk) + cross(vel, djerk);
+    let d_vel_cross_acc = cross(dvel, acc) + cross(vel, dacc);
+    let d_vel_dot_acc = dvel.dot(&acc) + vel.dot(&dacc);
+
+    let d_top = d_vel_cross_jerk * norm_squared + vel_cross_jerk * d_norm_squared
+        - 3.0 * (d_vel_cross_acc * vel_dot_acc + vel_cross_acc * d_vel_dot_acc);
+
+    d_top / norm_squared.powf(2.5) - 2.5 * top * d_norm_squared / norm_squared.powf(3.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curvature_gradient_matches_finite_differences() {
+        let start = Waypoint::new(0., 0., 2., 1., 0.5, -1.);
+        let end = Waypoint::new(3., 2., 1., 2., -1., 0.5);
+        let opts = HermiteOptions::default();
+        let tol = 1e-10;
+
+        let cost = |param: Vec4| {
+            let mut start = start.clone();
+            let mut end = end.clone();
+            start.curvature = [param[0], param[1]];
+            end.curvature = [param[2], param[3]];
+            hermite(&start, &end, &opts).integral_d_curvature_d_t_squared(tol)
+        };
+
+        let param = [0.5, -1., -1., 0.5];
+        let analytic = hermite(&start, &end, &opts).integral_d_curvature_d_t_squared_gradient(tol);
+        let h = 1e-5;
+
+        for i in 0..4 {
+            let mut up = param;
+            let mut down = param;
+            up[i] += h;
+            down[i] -= h;
+            let numeric = (cost(up) - cost(down)) / (2. * h);
+
+            assert!(
+                (analytic[i] - numeric).abs() < 1e-6 * numeric.abs().max(1.),
+                "component {}: {} vs {}",
+                i,
+                analytic[i],
+                numeric
+            );
+        }
     }
 }
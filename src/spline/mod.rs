@@ -1,23 +1,58 @@
+mod bezier;
+mod bspline;
+mod clothoid;
+mod cubic;
+mod directed;
+mod dubins;
 mod hermite;
+mod natural;
+mod primitive;
+mod reeds_shepp;
 
+pub use bezier::*;
+pub use bspline::*;
+pub use clothoid::*;
+pub use cubic::*;
+pub use directed::*;
+pub use dubins::*;
 pub use hermite::*;
+pub use natural::*;
+pub use primitive::*;
+pub use reeds_shepp::*;
 
-use crate::{Point, Vec2, Vector, Waypoint};
+use crate::{adaptive_simpson, Coordinate, Point, Vec2, Vector, WayfinderError, Waypoint};
+use serde::{Deserialize, Serialize};
 
-pub trait Spline<T> {
-    fn from_wps(start: &Waypoint, end: &Waypoint) -> T;
+const ARC_LENGTH_TOL: f64 = 1e-9;
+const MAX_NEWTON_ITERS: usize = 50;
+const PROJECTION_SAMPLES: usize = 16;
+const PROJECTION_HINT_WINDOW: f64 = 0.25;
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Projection {
+    pub segment: usize,
+    pub t: f64,
+    pub distance: f64,
+    pub cross_track: f64,
+}
+
+pub trait Spline {
     fn position(&self, t: f64) -> Vec2;
     fn velocity(&self, t: f64) -> Vec2;
     fn acceleration(&self, t: f64) -> Vec2;
     fn jerk(&self, t: f64) -> Vec2;
 
+    fn reversed(&self) -> bool {
+        false
+    }
+
     fn point_at(&self, t: f64) -> Point {
         Point {
             position: self.position(t),
             velocity: self.velocity(t),
             acceleration: self.acceleration(t),
             jerk: self.jerk(t),
+            reversed: self.reversed(),
         }
     }
 
@@ -26,15 +61,208 @@ pub trait Spline<T> {
         d.scale(1. / d.norm())
     }
 
-    fn curvature(&self, _: f64) -> f64 {
-        0.
+    fn curvature(&self, t: f64) -> f64 {
+        let vel = self.velocity(t);
+        let acc = self.acceleration(t);
+
+        (vel.x() * acc.y() - vel.y() * acc.x()) / vel.norm().powi(3)
+    }
+
+    fn d_curvature(&self, t: f64) -> f64 {
+        let vel = self.velocity(t);
+        let acc = self.acceleration(t);
+        let jerk = self.jerk(t);
+
+        let top = (vel.x() * jerk.y() - jerk.x() * vel.y()) * vel.norm_squared()
+            - 3.0
+                * (vel.x() * acc.y() - acc.x() * vel.y())
+                * (vel.x() * acc.x() + vel.y() * acc.y());
+        top / vel.norm().powi(5)
+    }
+
+    fn arc_length(&self, t0: f64, t1: f64) -> f64 {
+        adaptive_simpson(|t| self.velocity(t).norm(), t0, t1, ARC_LENGTH_TOL)
+    }
+
+    fn t_at_distance(&self, distance: f64) -> f64 {
+        let total = self.arc_length(0., 1.);
+        if distance <= 0. || total <= 0. {
+            return 0.;
+        }
+        if distance >= total {
+            return 1.;
+        }
+
+        // Newton iteration on arc_length(0, t) - distance, falling back to
+        // bisection whenever a step leaves the bracket
+        let (mut lo, mut hi) = (0., 1.);
+        let mut t = distance / total;
+        for _ in 0..MAX_NEWTON_ITERS {
+            let err = self.arc_length(0., t) - distance;
+            if err.abs() < ARC_LENGTH_TOL {
+                break;
+            }
+
+            if err > 0. {
+                hi = t;
+            } else {
+                lo = t;
+            }
+
+            let next = t - err / self.velocity(t).norm();
+            t = if next > lo && next < hi {
+                next
+            } else {
+                (lo + hi) / 2.
+            };
+        }
+
+        t
+    }
+
+    fn project(&self, position: Vec2, hint: Option<f64>) -> Projection {
+        match hint {
+            Some(t) => self.project_between(
+                position,
+                (t - PROJECTION_HINT_WINDOW).max(0.),
+                (t + PROJECTION_HINT_WINDOW).min(1.),
+            ),
+            None => self.project_between(position, 0., 1.),
+        }
+    }
+
+    fn project_between(&self, position: Vec2, t0: f64, t1: f64) -> Projection {
+        let dist_squared = |t: f64| self.position(t).add(position.scale(-1.)).norm_squared();
+
+        // coarse sampling picks the basin, Newton on (p(t) - q) . p'(t) refines it
+        let mut t = (0..=PROJECTION_SAMPLES)
+            .map(|i| t0 + (t1 - t0) * i as f64 / PROJECTION_SAMPLES as f64)
+            .fold(t0, |best, t| {
+                if dist_squared(t) < dist_squared(best) {
+                    t
+                } else {
+                    best
+                }
+            });
+
+        for _ in 0..MAX_NEWTON_ITERS {
+            let offset = self.position(t).add(position.scale(-1.));
+            let vel = self.velocity(t);
+            let f = offset.dot(&vel);
+            let df = vel.norm_squared() + offset.dot(&self.acceleration(t));
+
+            if df.abs() < 1e-12 {
+                break;
+            }
+
+            let next = (t - f / df).max(t0).min(t1);
+            if (next - t).abs() < 1e-12 {
+                t = next;
+                break;
+            }
+            t = next;
+        }
+
+        let offset = position.add(self.position(t).scale(-1.));
+        let heading = self.rotation(t);
+
+        Projection {
+            segment: 0,
+            t,
+            distance: offset.norm(),
+            cross_track: heading.x() * offset.y() - heading.y() * offset.x(),
+        }
     }
 
-    fn d_curvature(&self, _: f64) -> f64 {
-        0.
+    fn integral_d_curvature_d_t_squared(&self, tol: f64) -> f64 {
+        integrate(self, |s, t| s.d_curvature(t).powi(2), tol)
     }
 
-    fn integral_d_curvature_d_t_squared(&self, _: u64) -> f64 {
-        0.
+    fn integral_curvature_squared(&self, tol: f64) -> f64 {
+        integrate(self, |s, t| s.curvature(t).powi(2), tol)
     }
 }
+
+pub fn integrate<S: Spline + ?Sized, V: Vector + Copy, F: Fn(&S, f64) -> V>(
+    spline: &S,
+    f: F,
+    tol: f64,
+) -> V {
+    adaptive_simpson(|t| f(spline, t), 0., 1., tol)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TangentScale {
+    Fixed(f64),
+    Chord(f64),
+    Raw,
+}
+
+impl Default for TangentScale {
+    fn default() -> Self {
+        TangentScale::Chord(1.2)
+    }
+}
+
+impl TangentScale {
+    // a waypoint's own tangent_scale takes precedence over this one
+    pub fn apply(&self, start: &Waypoint, end: &Waypoint) -> (Vec2, Vec2) {
+        let chord = end.point.add(start.point.scale(-1.)).norm();
+        let scaled = |wp: &Waypoint| match wp.tangent_scale.as_ref().unwrap_or(self) {
+            TangentScale::Fixed(magnitude) => wp.tangent.unit().scale(*magnitude),
+            TangentScale::Chord(factor) => wp.tangent.unit().scale(factor * chord),
+            TangentScale::Raw => wp.tangent,
+        };
+
+        (scaled(start), scaled(end))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HermiteOptions {
+    pub tangent_scale: TangentScale,
+}
+
+pub trait FromWaypoints: Spline {
+    fn from_wps(start: &Waypoint, end: &Waypoint) -> Self;
+}
+
+pub fn try_splines<S: FromWaypoints>(wps: &[Waypoint]) -> Result<Vec<S>, WayfinderError> {
+    check_waypoints(wps)?;
+    Ok(splines(wps))
+}
+
+pub(crate) fn check_waypoints(wps: &[Waypoint]) -> Result<(), WayfinderError> {
+    if wps.len() < 2 {
+        return Err(WayfinderError::NotEnoughWaypoints { count: wps.len() });
+    }
+
+    for (index, wp) in wps.iter().enumerate() {
+        let finite = [wp.point, wp.tangent, wp.curvature]
+            .iter()
+            .all(|v| v.x().is_finite() && v.y().is_finite());
+        if !finite {
+            return Err(WayfinderError::NonFiniteWaypoint { index });
+        }
+
+        if wp.tangent.norm() == 0. {
+            return Err(WayfinderError::ZeroTangent { index });
+        }
+    }
+
+    for (index, (start, end)) in wps.iter().zip(wps.iter().skip(1)).enumerate() {
+        if end.point.add(start.point.scale(-1.)).norm() == 0. {
+            return Err(WayfinderError::CoincidentWaypoints { index });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn splines<S: FromWaypoints>(wps: &[Waypoint]) -> Vec<S> {
+    wps.iter()
+        .zip(wps.iter().skip(1))
+        .map(|(start, end)| S::from_wps(start, end))
+        .collect()
+}
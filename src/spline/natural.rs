@@ -0,0 +1,160 @@
+use crate::{
+    cubic_hermite, solve_tridiagonal, Coordinate, CubicHermite, Vec2, Vector, WayfinderError,
+};
+
+pub fn natural_cubic_spline(
+    points: &[Vec2],
+    start_heading: Option<Vec2>,
+    end_heading: Option<Vec2>,
+) -> Vec<CubicHermite> {
+    let n = points.len();
+    if n < 2 {
+        return Vec::new();
+    }
+
+    // chord-length knot spacing, so the solved derivatives are roughly unit speed
+    let h: Vec<f64> = points
+        .iter()
+        .zip(points.iter().skip(1))
+        .map(|(p0, p1)| p1.add(p0.scale(-1.)).norm())
+        .collect();
+    let slope = |i: usize| points[i + 1].add(points[i].scale(-1.)).scale(1. / h[i]);
+
+    let mut lower = vec![0.; n];
+    let mut diag = vec![0.; n];
+    let mut upper = vec![0.; n];
+    let mut rhs = vec![[0.; 2]; n];
+
+    match start_heading {
+        Some(heading) => {
+            diag[0] = 1.;
+            rhs[0] = heading.unit();
+        }
+        None => {
+            diag[0] = 2.;
+            upper[0] = 1.;
+            rhs[0] = slope(0).scale(3.);
+        }
+    }
+
+    for i in 1..n - 1 {
+        lower[i] = h[i];
+        diag[i] = 2. * (h[i - 1] + h[i]);
+        upper[i] = h[i - 1];
+        rhs[i] = slope(i - 1)
+            .scale(h[i])
+            .add(slope(i).scale(h[i - 1]))
+            .scale(3.);
+    }
+
+    match end_heading {
+        Some(heading) => {
+            diag[n - 1] = 1.;
+            rhs[n - 1] = heading.unit();
+        }
+        None => {
+            lower[n - 1] = 1.;
+            diag[n - 1] = 2.;
+            rhs[n - 1] = slope(n - 2).scale(3.);
+        }
+    }
+
+    let derivatives = solve_tridiagonal(&lower, &diag, &upper, &rhs);
+
+    (0..n - 1)
+        .map(|i| {
+            cubic_hermite(
+                points[i],
+                derivatives[i].scale(h[i]),
+                points[i + 1],
+                derivatives[i + 1].scale(h[i]),
+            )
+        })
+        .collect()
+}
+
+pub fn try_natural_cubic_spline(
+    points: &[Vec2],
+    start_heading: Option<Vec2>,
+    end_heading: Option<Vec2>,
+) -> Result<Vec<CubicHermite>, WayfinderError> {
+    let n = points.len();
+    if n < 2 {
+        return Err(WayfinderError::NotEnoughWaypoints { count: n });
+    }
+
+    let finite = |v: &Vec2| v.x().is_finite() && v.y().is_finite();
+    for (index, point) in points.iter().enumerate() {
+        if !finite(point) {
+            return Err(WayfinderError::NonFiniteWaypoint { index });
+        }
+    }
+
+    for (index, heading) in [(0, start_heading), (n - 1, end_heading)].iter() {
+        if let Some(heading) = heading {
+            if !finite(heading) {
+                return Err(WayfinderError::NonFiniteWaypoint { index: *index });
+            }
+            if heading.norm() == 0. {
+                return Err(WayfinderError::ZeroTangent { index: *index });
+            }
+        }
+    }
+
+    for (index, (p0, p1)) in points.iter().zip(points.iter().skip(1)).enumerate() {
+        if p1.add(p0.scale(-1.)).norm() == 0. {
+            return Err(WayfinderError::CoincidentWaypoints { index });
+        }
+    }
+
+    Ok(natural_cubic_spline(points, start_heading, end_heading))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Spline;
+
+    const EPS: f64 = 1e-9;
+
+    fn near(a: Vec2, b: Vec2) -> bool {
+        a.add(b.scale(-1.)).norm() < EPS
+    }
+
+    #[test]
+    fn natural_spline_is_c2_with_free_ends() {
+        let points = [[0., 0.], [1., 2.], [3., 3.], [4., 1.], [6., 2.]];
+        let segments = natural_cubic_spline(&points, None, None);
+        // each segment is parameterized over its chord, so derivatives with
+        // respect to the knot parameter are divided by the chord length
+        let h: Vec<f64> = points
+            .iter()
+            .zip(points.iter().skip(1))
+            .map(|(p0, p1)| p1.add(p0.scale(-1.)).norm())
+            .collect();
+
+        for (i, (s0, s1)) in segments.iter().zip(segments.iter().skip(1)).enumerate() {
+            assert!(near(s0.position(1.), s1.position(0.)));
+            assert!(near(
+                s0.velocity(1.).scale(1. / h[i]),
+                s1.velocity(0.).scale(1. / h[i + 1])
+            ));
+            assert!(near(
+                s0.acceleration(1.).scale(1. / (h[i] * h[i])),
+                s1.acceleration(0.).scale(1. / (h[i + 1] * h[i + 1]))
+            ));
+        }
+
+        assert!(near(segments[0].acceleration(0.), [0., 0.]));
+        assert!(near(segments[3].acceleration(1.), [0., 0.]));
+    }
+
+    #[test]
+    fn clamped_ends_follow_the_headings() {
+        let points = [[0., 0.], [2., 1.], [4., 0.]];
+        let segments = natural_cubic_spline(&points, Some([0., 3.]), Some([1., -1.]));
+
+        assert!(near(segments[0].velocity(0.).unit(), [0., 1.]));
+        assert!(near(segments[1].velocity(1.).unit(), [1., -1.].unit()));
+    }
+}
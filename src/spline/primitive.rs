@@ -0,0 +1,127 @@
+use crate::{Coordinate, Pose, Spline, Translation, Twist, Vec2, Vector};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Gear {
+    Forward,
+    Reverse,
+}
+
+impl Gear {
+    pub fn sign(&self) -> f64 {
+        match self {
+            Gear::Forward => 1.,
+            Gear::Reverse => -1.,
+        }
+    }
+}
+
+// A line (zero curvature) or circular arc, driven in either gear. Curvature
+// is relative to the robot, so a left turn in reverse decreases the heading.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PathPrimitive {
+    pub start: Pose,
+    pub curvature: f64,
+    pub length: f64,
+    pub gear: Gear,
+}
+
+impl PathPrimitive {
+    pub fn new(start: Pose, curvature: f64, length: f64, gear: Gear) -> Self {
+        PathPrimitive {
+            start,
+            curvature,
+            length,
+            gear,
+        }
+    }
+
+    pub fn pose_at(&self, t: f64) -> Pose {
+        let d = self.gear.sign() * self.length * t;
+        self.start
+            .compose(&Pose::exp(&Twist::new(d, 0., self.curvature * d)))
+    }
+
+    pub fn end_pose(&self) -> Pose {
+        self.pose_at(1.)
+    }
+}
+
+pub fn primitives_length(primitives: &[PathPrimitive]) -> f64 {
+    primitives.iter().map(|primitive| primitive.length).sum()
+}
+
+impl Spline for PathPrimitive {
+    fn position(&self, t: f64) -> Vec2 {
+        self.pose_at(t).translation()
+    }
+
+    fn velocity(&self, t: f64) -> Vec2 {
+        self.pose_at(t)
+            .rotation()
+            .scale(self.gear.sign() * self.length)
+    }
+
+    fn acceleration(&self, t: f64) -> Vec2 {
+        Translation::rotate_by(self.pose_at(t).rotation(), [0., 1.])
+            .scale(self.length * self.length * self.curvature)
+    }
+
+    fn jerk(&self, t: f64) -> Vec2 {
+        self.pose_at(t)
+            .rotation()
+            .scale(-self.gear.sign() * self.length.powi(3) * self.curvature * self.curvature)
+    }
+
+    fn reversed(&self) -> bool {
+        self.gear == Gear::Reverse
+    }
+
+    fn arc_length(&self, t0: f64, t1: f64) -> f64 {
+        (t1 - t0) * self.length
+    }
+
+    fn t_at_distance(&self, distance: f64) -> f64 {
+        if self.length <= 0. {
+            return 0.;
+        }
+        (distance / self.length).clamp(0., 1.)
+    }
+}
+
+// the turning radius must be positive and both poses finite
+pub(crate) fn well_posed(start: &Pose, end: &Pose, radius: f64) -> bool {
+    let finite = [
+        start.translation(),
+        start.rotation(),
+        end.translation(),
+        end.rotation(),
+    ]
+    .iter()
+    .all(|v| v.x().is_finite() && v.y().is_finite());
+    finite && radius > 0. && radius.is_finite()
+}
+
+// Chains unit-radius steering words, (steering, signed length) with steering
+// in {-1, 0, 1}, into primitives of the given turning radius.
+pub(crate) fn chain(start: &Pose, radius: f64, word: &[(f64, f64)]) -> Vec<PathPrimitive> {
+    let mut pose = *start;
+    let mut primitives = Vec::with_capacity(word.len());
+
+    for &(steering, length) in word {
+        if length.abs() < 1e-9 {
+            continue;
+        }
+
+        let gear = if length < 0. {
+            Gear::Reverse
+        } else {
+            Gear::Forward
+        };
+        let primitive = PathPrimitive::new(pose, steering / radius, length.abs() * radius, gear);
+        pose = primitive.end_pose();
+        primitives.push(primitive);
+    }
+
+    primitives
+}
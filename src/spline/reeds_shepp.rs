@@ -0,0 +1,346 @@
+use crate::{chain, well_posed, PathPrimitive, Pose};
+use std::f64::consts::{FRAC_PI_2, PI};
+
+const LEFT: f64 = 1.;
+const STRAIGHT: f64 = 0.;
+const RIGHT: f64 = -1.;
+const ZERO: f64 = 1e-10;
+
+// Shortest path with a minimum turning radius that may change gear, after
+// Reeds & Shepp (1990) with the word formulas of OMPL's implementation.
+// Words are solved in the start frame for a unit radius; the time-flip
+// (-x, y, -phi) and reflection (x, -y, -phi) symmetries reuse each formula.
+// None for a non-positive radius or non-finite poses.
+pub fn reeds_shepp(start: &Pose, end: &Pose, radius: f64) -> Option<Vec<PathPrimitive>> {
+    if !well_posed(start, end, radius) {
+        return None;
+    }
+
+    let goal = end.relative_to(start);
+    let (x, y, phi) = (goal.x() / radius, goal.y() / radius, goal.heading());
+
+    let mut words = Vec::new();
+    csc(x, y, phi, &mut words);
+    ccc(x, y, phi, &mut words);
+    cccc(x, y, phi, &mut words);
+    ccsc(x, y, phi, &mut words);
+    ccscc(x, y, phi, &mut words);
+
+    let cost = |word: &Vec<(f64, f64)>| word.iter().map(|(_, length)| length.abs()).sum::<f64>();
+
+    words
+        .iter()
+        .min_by(|w0, w1| cost(w0).total_cmp(&cost(w1)))
+        .map(|word| chain(start, radius, word))
+}
+
+fn mod2pi(x: f64) -> f64 {
+    let v = x.rem_euclid(2. * PI);
+    if v > PI {
+        v - 2. * PI
+    } else {
+        v
+    }
+}
+
+fn polar(x: f64, y: f64) -> (f64, f64) {
+    (x.hypot(y), y.atan2(x))
+}
+
+fn tau_omega(u: f64, v: f64, xi: f64, eta: f64, phi: f64) -> (f64, f64) {
+    let delta = mod2pi(u - v);
+    let a = u.sin() - delta.sin();
+    let b = u.cos() - delta.cos() - 1.;
+    let t1 = (eta * a - xi * b).atan2(xi * a + eta * b);
+    let t2 = 2. * (delta.cos() - v.cos() - u.cos()) + 3.;
+    let tau = if t2 < 0. { mod2pi(t1 + PI) } else { mod2pi(t1) };
+    (tau, mod2pi(tau - u + v - phi))
+}
+
+// the goal seen from the end of the path, used to solve words backwards
+fn backwards(x: f64, y: f64, phi: f64) -> (f64, f64) {
+    (x * phi.cos() + y * phi.sin(), x * phi.sin() - y * phi.cos())
+}
+
+fn flip(steering: &[f64], lengths: &[f64], reflect: bool, timeflip: bool) -> Vec<(f64, f64)> {
+    steering
+        .iter()
+        .zip(lengths)
+        .map(|(&s, &l)| (if reflect { -s } else { s }, if timeflip { -l } else { l }))
+        .collect()
+}
+
+// solves a word in all four symmetric variants of (x, y, phi)
+fn symmetric<F: Fn(f64, f64, f64) -> Option<Vec<f64>>>(
+    x: f64,
+    y: f64,
+    phi: f64,
+    steering: &[f64],
+    solve: F,
+    words: &mut Vec<Vec<(f64, f64)>>,
+) {
+    let variants = [
+        (x, y, phi, false, false),
+        (-x, y, -phi, false, true),
+        (x, -y, -phi, true, false),
+        (-x, -y, phi, true, true),
+    ];
+
+    for &(x, y, phi, reflect, timeflip) in &variants {
+        if let Some(lengths) = solve(x, y, phi) {
+            words.push(flip(steering, &lengths, reflect, timeflip));
+        }
+    }
+}
+
+fn lp_sp_lp(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let (u, t) = polar(x - phi.sin(), y - 1. + phi.cos());
+    if t >= -ZERO {
+        let v = mod2pi(phi - t);
+        if v >= -ZERO {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+fn lp_sp_rp(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let (u1, t1) = polar(x + phi.sin(), y - 1. - phi.cos());
+    let u1 = u1 * u1;
+    if u1 >= 4. {
+        let u = (u1 - 4.).sqrt();
+        let t = mod2pi(t1 + 2f64.atan2(u));
+        let v = mod2pi(t - phi);
+        if t >= -ZERO && v >= -ZERO {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+fn csc(x: f64, y: f64, phi: f64, words: &mut Vec<Vec<(f64, f64)>>) {
+    let solve = |x, y, phi| lp_sp_lp(x, y, phi).map(|(t, u, v)| vec![t, u, v]);
+    symmetric(x, y, phi, &[LEFT, STRAIGHT, LEFT], solve, words);
+
+    let solve = |x, y, phi| lp_sp_rp(x, y, phi).map(|(t, u, v)| vec![t, u, v]);
+    symmetric(x, y, phi, &[LEFT, STRAIGHT, RIGHT], solve, words);
+}
+
+fn lp_rm_l(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let (u1, theta) = polar(x - phi.sin(), y - 1. + phi.cos());
+    if u1 <= 4. {
+        let u = -2. * (0.25 * u1).asin();
+        let t = mod2pi(theta + 0.5 * u + PI);
+        let v = mod2pi(phi - t + u);
+        if t >= -ZERO && u <= ZERO {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+fn ccc(x: f64, y: f64, phi: f64, words: &mut Vec<Vec<(f64, f64)>>) {
+    let solve = |x, y, phi| lp_rm_l(x, y, phi).map(|(t, u, v)| vec![t, u, v]);
+    symmetric(x, y, phi, &[LEFT, RIGHT, LEFT], solve, words);
+
+    let (xb, yb) = backwards(x, y, phi);
+    let solve = |x, y, phi| lp_rm_l(x, y, phi).map(|(t, u, v)| vec![v, u, t]);
+    symmetric(xb, yb, phi, &[LEFT, RIGHT, LEFT], solve, words);
+}
+
+fn lp_rup_lum_rm(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let xi = x + phi.sin();
+    let eta = y - 1. - phi.cos();
+    let rho = 0.25 * (2. + xi.hypot(eta));
+    if rho <= 1. {
+        let u = rho.acos();
+        let (t, v) = tau_omega(u, -u, xi, eta, phi);
+        if t >= -ZERO && v <= ZERO {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+fn lp_rum_lum_rp(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let xi = x + phi.sin();
+    let eta = y - 1. - phi.cos();
+    let rho = (20. - xi * xi - eta * eta) / 16.;
+    if (0. ..=1.).contains(&rho) {
+        let u = -rho.acos();
+        if u >= -FRAC_PI_2 {
+            let (t, v) = tau_omega(u, u, xi, eta, phi);
+            if t >= -ZERO && v >= -ZERO {
+                return Some((t, u, v));
+            }
+        }
+    }
+    None
+}
+
+fn cccc(x: f64, y: f64, phi: f64, words: &mut Vec<Vec<(f64, f64)>>) {
+    let steering = [LEFT, RIGHT, LEFT, RIGHT];
+
+    let solve = |x, y, phi| lp_rup_lum_rm(x, y, phi).map(|(t, u, v)| vec![t, u, -u, v]);
+    symmetric(x, y, phi, &steering, solve, words);
+
+    let solve = |x, y, phi| lp_rum_lum_rp(x, y, phi).map(|(t, u, v)| vec![t, u, u, v]);
+    symmetric(x, y, phi, &steering, solve, words);
+}
+
+fn lp_rm_sm_lm(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let (rho, theta) = polar(x - phi.sin(), y - 1. + phi.cos());
+    if rho >= 2. {
+        let r = (rho * rho - 4.).sqrt();
+        let u = 2. - r;
+        let t = mod2pi(theta + r.atan2(-2.));
+        let v = mod2pi(phi - FRAC_PI_2 - t);
+        if t >= -ZERO && u <= ZERO && v <= ZERO {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+fn lp_rm_sm_rm(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let xi = x + phi.sin();
+    let eta = y - 1. - phi.cos();
+    let (rho, theta) = polar(-eta, xi);
+    if rho >= 2. {
+        let t = theta;
+        let u = 2. - rho;
+        let v = mod2pi(t + FRAC_PI_2 - phi);
+        if t >= -ZERO && u <= ZERO && v <= ZERO {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+fn ccsc(x: f64, y: f64, phi: f64, words: &mut Vec<Vec<(f64, f64)>>) {
+    let solve = |x, y, phi| lp_rm_sm_lm(x, y, phi).map(|(t, u, v)| vec![t, -FRAC_PI_2, u, v]);
+    symmetric(x, y, phi, &[LEFT, RIGHT, STRAIGHT, LEFT], solve, words);
+
+    let solve = |x, y, phi| lp_rm_sm_rm(x, y, phi).map(|(t, u, v)| vec![t, -FRAC_PI_2, u, v]);
+    symmetric(x, y, phi, &[LEFT, RIGHT, STRAIGHT, RIGHT], solve, words);
+
+    let (xb, yb) = backwards(x, y, phi);
+
+    let solve = |x, y, phi| lp_rm_sm_lm(x, y, phi).map(|(t, u, v)| vec![v, u, -FRAC_PI_2, t]);
+    symmetric(xb, yb, phi, &[LEFT, STRAIGHT, RIGHT, LEFT], solve, words);
+
+    let solve = |x, y, phi| lp_rm_sm_rm(x, y, phi).map(|(t, u, v)| vec![v, u, -FRAC_PI_2, t]);
+    symmetric(xb, yb, phi, &[RIGHT, STRAIGHT, RIGHT, LEFT], solve, words);
+}
+
+fn lp_rm_s_lm_rp(x: f64, y: f64, phi: f64) -> Option<(f64, f64, f64)> {
+    let xi = x + phi.sin();
+    let eta = y - 1. - phi.cos();
+    let (rho, _) = polar(xi, eta);
+    if rho >= 2. {
+        let u = 4. - (rho * rho - 4.).sqrt();
+        if u <= ZERO {
+            let t = mod2pi(((4. - u) * xi - 2. * eta).atan2(-2. * xi + (u - 4.) * eta));
+            let v = mod2pi(t - phi);
+            if t >= -ZERO && v >= -ZERO {
+                return Some((t, u, v));
+            }
+        }
+    }
+    None
+}
+
+fn ccscc(x: f64, y: f64, phi: f64, words: &mut Vec<Vec<(f64, f64)>>) {
+    let solve =
+        |x, y, phi| lp_rm_s_lm_rp(x, y, phi).map(|(t, u, v)| vec![t, -FRAC_PI_2, u, -FRAC_PI_2, v]);
+    symmetric(
+        x,
+        y,
+        phi,
+        &[LEFT, RIGHT, STRAIGHT, LEFT, RIGHT],
+        solve,
+        words,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dubins, primitives_length, Gear, Rotation, Translation, Vector};
+    use proptest::prelude::*;
+
+    const EPS: f64 = 1e-6;
+
+    fn pose(x: f64, y: f64, theta: f64) -> Pose {
+        Pose::new([x, y], [theta.cos(), theta.sin()])
+    }
+
+    fn assert_reaches(primitives: &[PathPrimitive], start: &Pose, end: &Pose) {
+        let mut pose = *start;
+        for primitive in primitives {
+            assert!(
+                primitive
+                    .start
+                    .translation()
+                    .add(pose.translation().scale(-1.))
+                    .norm()
+                    < EPS
+            );
+            pose = primitive.end_pose();
+        }
+
+        assert!(pose.translation().add(end.translation().scale(-1.)).norm() < EPS);
+        assert!(
+            Translation::rotate_by(pose.rotation(), end.rotation().inverse())
+                .as_radians()
+                .abs()
+                < EPS
+        );
+    }
+
+    prop_compose! {
+        fn arb_pose()(x in -10.0..10.0, y in -10.0..10.0, theta in -PI..PI) -> Pose {
+            pose(x, y, theta)
+        }
+    }
+
+    #[test]
+    fn straight_behind_is_a_reverse_line() {
+        let (start, end) = (pose(0., 0., 0.), pose(-3., 0., 0.));
+        let primitives = reeds_shepp(&start, &end, 1.).unwrap();
+
+        assert_eq!(primitives.len(), 1);
+        assert_eq!(primitives[0].gear, Gear::Reverse);
+        assert!((primitives_length(&primitives) - 3.).abs() < EPS);
+        assert_reaches(&primitives, &start, &end);
+    }
+
+    #[test]
+    fn ill_posed_input_is_none() {
+        let (start, end) = (pose(0., 0., 0.), pose(5., 0., 0.));
+
+        assert!(reeds_shepp(&start, &end, 0.).is_none());
+        assert!(reeds_shepp(&start, &end, -1.).is_none());
+        assert!(reeds_shepp(&start, &end, f64::INFINITY).is_none());
+        assert!(reeds_shepp(&pose(0., f64::NAN, 0.), &end, 1.).is_none());
+        assert!(reeds_shepp(&start, &pose(0., 0., f64::NAN), 1.).is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn reaches_the_goal(start in arb_pose(), end in arb_pose(), radius in 0.2..3.0) {
+            let primitives = reeds_shepp(&start, &end, radius).unwrap();
+
+            assert_reaches(&primitives, &start, &end);
+            prop_assert!(primitives.iter().all(|p| p.curvature.abs() <= 1. / radius + EPS));
+        }
+
+        #[test]
+        fn never_longer_than_dubins(start in arb_pose(), end in arb_pose(), radius in 0.2..3.0) {
+            let rs = primitives_length(&reeds_shepp(&start, &end, radius).unwrap());
+            let forward = primitives_length(&dubins(&start, &end, radius).unwrap());
+
+            prop_assert!(rs <= forward + EPS, "{} > {}", rs, forward);
+        }
+    }
+}
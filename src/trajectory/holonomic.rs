@@ -0,0 +1,341 @@
+use crate::{Path, Rotation, Spline, State, Trajectory, Translation, Vec2, Vector, Waypoint};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HolonomicConfig {
+    max_angular_velocity: f64,
+    max_angular_acceleration: f64,
+}
+
+impl HolonomicConfig {
+    pub fn new(max_angular_velocity: f64, max_angular_acceleration: f64) -> Self {
+        HolonomicConfig {
+            max_angular_velocity,
+            max_angular_acceleration,
+        }
+    }
+
+    // both limits must be positive; an infinite velocity limit is allowed
+    pub fn is_valid(&self) -> bool {
+        self.max_angular_velocity > 0.
+            && self.max_angular_acceleration > 0.
+            && self.max_angular_acceleration.is_finite()
+    }
+}
+
+// Rest-to-rest trapezoidal move through `distance` radians, stretched to
+// fill `duration` when the limits allow it to finish sooner. Stopping at
+// every target keeps each move independent of the next, at the cost of
+// turning slower than the limits allow through intermediate waypoints.
+#[derive(Debug, Clone, Copy)]
+struct AngularProfile {
+    start_time: f64,
+    start: f64,
+    distance: f64,
+    cruise: f64,
+    acceleration: f64,
+    accel_time: f64,
+    total_time: f64,
+}
+
+impl AngularProfile {
+    fn new(
+        start_time: f64,
+        start: f64,
+        distance: f64,
+        duration: f64,
+        config: &HolonomicConfig,
+    ) -> Self {
+        let (max_velocity, acceleration) =
+            (config.max_angular_velocity, config.max_angular_acceleration);
+        let d = distance.abs();
+
+        let fastest_cruise = max_velocity.min((d * acceleration).sqrt());
+        let fastest_time = if fastest_cruise > 0. {
+            fastest_cruise / acceleration + d / fastest_cruise
+        } else {
+            0.
+        };
+
+        // solve d = v * (duration - v / a) for the slower cruise velocity
+        let cruise = if fastest_time < duration {
+            let disc = acceleration * acceleration * duration * duration - 4. * acceleration * d;
+            (acceleration * duration - disc.max(0.).sqrt()) / 2.
+        } else {
+            fastest_cruise
+        };
+
+        let accel_time = cruise / acceleration;
+        let total_time = if cruise > 0. {
+            accel_time + d / cruise
+        } else {
+            0.
+        };
+
+        AngularProfile {
+            start_time,
+            start,
+            distance,
+            cruise,
+            acceleration,
+            accel_time,
+            total_time,
+        }
+    }
+
+    fn end_time(&self) -> f64 {
+        self.start_time + self.total_time
+    }
+
+    // (angle, angular velocity) at the given time
+    fn sample(&self, time: f64) -> (f64, f64) {
+        let tau = (time - self.start_time).max(0.);
+        let (travelled, velocity) = if tau >= self.total_time {
+            (self.distance.abs(), 0.)
+        } else if tau < self.accel_time {
+            (0.5 * self.acceleration * tau * tau, self.acceleration * tau)
+        } else if tau < self.total_time - self.accel_time {
+            (
+                0.5 * self.cruise * self.accel_time + self.cruise * (tau - self.accel_time),
+                self.cruise,
+            )
+        } else {
+            let remaining = self.total_time - tau;
+            (
+                self.distance.abs() - 0.5 * self.acceleration * remaining * remaining,
+                self.acceleration * remaining,
+            )
+        };
+
+        let sign = self.distance.signum();
+        (self.start + sign * travelled, sign * velocity)
+    }
+}
+
+// Replaces the robot facing of every state with a profile through the
+// waypoints' holonomic rotation targets, where `path` holds the segments the
+// trajectory was built from, one fewer than there are waypoints. Each
+// waypoint is matched by its distance along the path, scaled to the
+// trajectory's own, so paths that revisit a position keep their order.
+// The robot turns the short way between consecutive targets and comes to a
+// stop at each one, arriving late when the limits do not allow it in time.
+// It faces the first target from the start and holds the last one to the
+// end; without any targets the trajectory is returned unchanged.
+// None when the config is not valid, a target has no direction or the
+// waypoints do not match the path.
+pub fn holonomic_profile<S: Spline>(
+    trajectory: &Trajectory,
+    path: &Path<S>,
+    wps: &[Waypoint],
+    config: &HolonomicConfig,
+) -> Option<Trajectory> {
+    if !config.is_valid() || wps.len() != path.segments().len() + 1 {
+        return None;
+    }
+
+    let states = trajectory.states();
+    if states.is_empty() {
+        return Some(trajectory.clone());
+    }
+
+    let total = states[states.len() - 1].distance - states[0].distance;
+    let scale = if path.length() > 0. {
+        total / path.length()
+    } else {
+        0.
+    };
+
+    let mut targets: Vec<(f64, Vec2)> = Vec::new();
+    for (i, wp) in wps.iter().enumerate() {
+        if let Some(rotation) = wp.holonomic_rotation {
+            let along = path.distance_at(i, 0.).unwrap_or_else(|| path.length());
+            let time = time_at_distance(states, states[0].distance + along * scale);
+            targets.push((time, rotation.try_unit()?));
+        }
+    }
+
+    let mut angle = match targets.first() {
+        Some(&(_, rotation)) => rotation.as_radians(),
+        None => return Some(trajectory.clone()),
+    };
+
+    let mut profiles = Vec::with_capacity(targets.len());
+    let mut ready = states[0].time;
+    for &(time, rotation) in &targets {
+        let delta =
+            Translation::rotate_by(rotation, Vec2::from_radians(angle).inverse()).as_radians();
+        let profile = AngularProfile::new(ready, angle, delta, time - ready, config);
+
+        angle += delta;
+        ready = profile.end_time().max(time);
+        profiles.push(profile);
+    }
+
+    let mut out = trajectory.clone();
+    let mut current = 0;
+    for state in out.states.iter_mut() {
+        while current + 1 < profiles.len() && profiles[current + 1].start_time <= state.time {
+            current += 1;
+        }
+
+        let (angle, angular_velocity) = profiles[current].sample(state.time);
+
+        state.holonomic_rotation = Vec2::from_radians(angle);
+        state.angular_velocity = angular_velocity;
+    }
+
+    Some(out)
+}
+
+// distance never decreases along a trajectory, so the first state at or past
+// it brackets the time, interpolated linearly within the step
+fn time_at_distance(states: &[State], distance: f64) -> f64 {
+    let next = states.partition_point(|state| state.distance < distance);
+
+    match (next.checked_sub(1).map(|i| &states[i]), states.get(next)) {
+        (Some(s0), Some(s1)) if s1.distance > s0.distance => {
+            let frac = (distance - s0.distance) / (s1.distance - s0.distance);
+            s0.time + frac * (s1.time - s0.time)
+        }
+        (_, Some(state)) | (Some(state), None) => state.time,
+        (None, None) => 0.,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hermites, time_parameterize, Hermite, Jaci, Parameterizer, TrajectoryConfig};
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    const EPS: f64 = 1e-9;
+
+    fn build(wps: &[Waypoint]) -> (Path<Hermite>, Trajectory) {
+        let splines = hermites(wps);
+        let points = Jaci::new(0.05, 0.01).parameterize(&splines);
+        let trajectory = time_parameterize(&points, &TrajectoryConfig::new(1., 1., 0., 0.), &[]);
+        (Path::new(splines), trajectory)
+    }
+
+    fn angle_between(a: Vec2, b: Vec2) -> f64 {
+        Translation::rotate_by(a, b.inverse()).as_radians().abs()
+    }
+
+    fn square_loop() -> Vec<Waypoint> {
+        vec![
+            Waypoint::new(0., 0., 3., 0., 0., 0.).with_holonomic_rotation([1., 0.]),
+            Waypoint::new(3., 0., 0., 3., 0., 0.).with_holonomic_rotation([0., 1.]),
+            Waypoint::new(3., 3., -3., 0., 0., 0.).with_holonomic_rotation([-1., 0.]),
+            Waypoint::new(0., 0., 0., -3., 0., 0.).with_holonomic_rotation([0., -1.]),
+        ]
+    }
+
+    #[test]
+    fn starts_and_ends_at_the_targets() {
+        let wps = square_loop();
+        let (path, trajectory) = build(&wps);
+        let profiled =
+            holonomic_profile(&trajectory, &path, &wps, &HolonomicConfig::new(2., 4.)).unwrap();
+        let states = profiled.states();
+
+        assert!(angle_between(states[0].holonomic_rotation, [1., 0.]) < EPS);
+        assert!(angle_between(states[states.len() - 1].holonomic_rotation, [0., -1.]) < EPS);
+        assert_eq!(states[states.len() - 1].angular_velocity, 0.);
+    }
+
+    #[test]
+    fn respects_the_angular_limits() {
+        let wps = square_loop();
+        let (path, trajectory) = build(&wps);
+        let profiled =
+            holonomic_profile(&trajectory, &path, &wps, &HolonomicConfig::new(0.4, 0.5)).unwrap();
+        let states = profiled.states();
+
+        assert!(states
+            .iter()
+            .all(|state| state.angular_velocity.abs() <= 0.4 + EPS));
+        assert!(states
+            .iter()
+            .any(|state| state.angular_velocity.abs() > 0.39));
+        for (s0, s1) in states.iter().zip(states.iter().skip(1)) {
+            let dt = s1.time - s0.time;
+            if dt > 0. {
+                assert!(((s1.angular_velocity - s0.angular_velocity) / dt).abs() <= 0.5 + 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn revisited_position_keeps_waypoint_order() {
+        // the last waypoint sits on the first, so matching by position alone
+        // could not tell them apart
+        let wps = square_loop();
+        let (path, trajectory) = build(&wps);
+        let profiled =
+            holonomic_profile(&trajectory, &path, &wps, &HolonomicConfig::new(2., 4.)).unwrap();
+        let states = profiled.states();
+
+        let mut from = 0;
+        for wp in &wps {
+            let target = wp.holonomic_rotation.unwrap();
+            from += states[from..]
+                .iter()
+                .position(|state| angle_between(state.holonomic_rotation, target) < 0.02)
+                .unwrap();
+        }
+
+        let half = states.len() / 2;
+        assert!(states[..half]
+            .iter()
+            .all(|state| angle_between(state.holonomic_rotation, [0., -1.]) > FRAC_PI_2 - 0.02));
+    }
+
+    #[test]
+    fn short_turn_between_targets() {
+        let wps = vec![
+            Waypoint::new(0., 0., 1., 0., 0., 0.)
+                .with_holonomic_rotation(Vec2::from_radians(0.9 * PI)),
+            Waypoint::new(4., 0., 1., 0., 0., 0.)
+                .with_holonomic_rotation(Vec2::from_radians(-0.9 * PI)),
+        ];
+        let (path, trajectory) = build(&wps);
+        let profiled =
+            holonomic_profile(&trajectory, &path, &wps, &HolonomicConfig::new(2., 4.)).unwrap();
+
+        assert!(profiled
+            .states()
+            .iter()
+            .all(|state| state.angular_velocity >= 0.));
+    }
+
+    #[test]
+    fn without_targets_the_trajectory_is_unchanged() {
+        let wps = [
+            Waypoint::new(0., 0., 1., 0., 0., 0.),
+            Waypoint::new(2., 1., 1., 0., 0., 0.),
+        ];
+        let (path, trajectory) = build(&wps);
+        let profiled =
+            holonomic_profile(&trajectory, &path, &wps, &HolonomicConfig::new(2., 4.)).unwrap();
+
+        for (a, b) in profiled.states().iter().zip(trajectory.states()) {
+            assert_eq!(a.holonomic_rotation, b.holonomic_rotation);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        let wps = square_loop();
+        let (path, trajectory) = build(&wps);
+        let config = HolonomicConfig::new(2., 4.);
+
+        assert!(
+            holonomic_profile(&trajectory, &path, &wps, &HolonomicConfig::new(0., 4.)).is_none()
+        );
+        assert!(holonomic_profile(&trajectory, &path, &wps[..3], &config).is_none());
+
+        let mut zero = wps.clone();
+        zero[1] = zero[1].clone().with_holonomic_rotation([0., 0.]);
+        assert!(holonomic_profile(&trajectory, &path, &zero, &config).is_none());
+    }
+}
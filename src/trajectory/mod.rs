@@ -0,0 +1,501 @@
+mod holonomic;
+
+pub use holonomic::*;
+
+use crate::{
+    AccelerationLimits, Point, Pose, Rotation, TrajectoryConstraint, Translation, Vec2, Vector,
+    WayfinderError,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TrajectoryConfig {
+    max_velocity: f64,
+    max_acceleration: f64,
+    start_velocity: f64,
+    end_velocity: f64,
+    // flips the driving direction of every point
+    #[serde(default)]
+    reversed: bool,
+}
+
+impl TrajectoryConfig {
+    pub fn new(
+        max_velocity: f64,
+        max_acceleration: f64,
+        start_velocity: f64,
+        end_velocity: f64,
+    ) -> Self {
+        TrajectoryConfig {
+            max_velocity,
+            max_acceleration,
+            start_velocity,
+            end_velocity,
+            reversed: false,
+        }
+    }
+
+    pub fn with_reversed(self, reversed: bool) -> Self {
+        TrajectoryConfig { reversed, ..self }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct State {
+    pub time: f64,
+    pub distance: f64,
+    pub pose: Pose,
+    pub velocity: f64,
+    pub acceleration: f64,
+    pub curvature: f64,
+    // robot facing and its rate; these follow the pose unless a holonomic
+    // profile has been applied
+    pub holonomic_rotation: Vec2,
+    pub angular_velocity: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trajectory {
+    states: Vec<State>,
+}
+
+impl Trajectory {
+    pub fn states(&self) -> &[State] {
+        &self.states
+    }
+
+    pub fn total_time(&self) -> f64 {
+        self.states.last().map_or(0., |state| state.time)
+    }
+
+    // None for an empty trajectory or a time outside of it
+    pub fn sample(&self, time: f64) -> Option<State> {
+        let first = self.states.first()?;
+        let last = self.states.last()?;
+
+        if !(first.time..=last.time).contains(&time) {
+            return None;
+        }
+        if time == last.time {
+            return Some(*last);
+        }
+
+        let idx = self.states.partition_point(|state| state.time <= time);
+        Some(interpolate(&self.states[idx - 1], &self.states[idx], time))
+    }
+}
+
+pub fn time_parameterize(
+    points: &[Point],
+    config: &TrajectoryConfig,
+    constraints: &[&dyn TrajectoryConstraint],
+) -> Trajectory {
+    try_time_parameterize(points, config, constraints).unwrap_or_else(|err| panic!("{}", err))
+}
+
+pub fn try_time_parameterize(
+    points: &[Point],
+    config: &TrajectoryConfig,
+    constraints: &[&dyn TrajectoryConstraint],
+) -> Result<Trajectory, WayfinderError> {
+    // index of the input point each working point came from
+    let mut origins: Vec<usize> = (0..points.len()).collect();
+    let mut points = points.to_vec();
+    let mut refined = false;
+
+    let (ds, directions, velocities) = loop {
+        let ds: Vec<f64> = points
+            .iter()
+            .zip(points.iter().skip(1))
+            .map(|(p0, p1)| p1.position.add(p0.position.scale(-1.)).norm())
+            .collect();
+        let directions: Vec<f64> = points
+            .iter()
+            .map(|point| {
+                if point.reversed != config.reversed {
+                    -1.
+                } else {
+                    1.
+                }
+            })
+            .collect();
+        let velocities = velocity_profile(&points, &ds, &directions, config, constraints);
+
+        // a step that starts and ends at rest can't be covered at constant
+        // acceleration, so give it a midpoint to speed up and slow down around
+        let stalled: Vec<usize> = (0..ds.len())
+            .filter(|&i| ds[i] > 0. && velocities[i] + velocities[i + 1] <= 0.)
+            .collect();
+        if stalled.is_empty() {
+            break (ds, directions, velocities);
+        }
+        if refined {
+            return Err(WayfinderError::InfeasibleProfile {
+                index: origins[stalled[0]],
+            });
+        }
+
+        for &i in stalled.iter().rev() {
+            points.insert(i + 1, midpoint(&points[i], &points[i + 1]));
+            origins.insert(i + 1, origins[i]);
+        }
+        refined = true;
+    };
+
+    let flip = if config.reversed { -1. } else { 1. };
+    let mut states = Vec::with_capacity(points.len());
+    let mut time = 0.;
+    let mut distance = 0.;
+
+    for (i, point) in points.iter().enumerate() {
+        let heading = point
+            .try_heading()
+            .ok_or(WayfinderError::UndefinedHeading { index: origins[i] })?
+            .scale(flip);
+        let acceleration = if i + 1 < points.len() && ds[i] > 1e-9 {
+            (velocities[i + 1].powi(2) - velocities[i].powi(2)) / (2. * ds[i])
+        } else {
+            0.
+        };
+
+        // speeds are signed by the direction of travel; the step to the
+        // next point is driven in that point's direction
+        let velocity = directions[i] * velocities[i];
+        let curvature = point.curvature() * flip;
+
+        states.push(State {
+            time,
+            distance,
+            pose: Pose::new(point.position, heading),
+            velocity,
+            acceleration: directions[(i + 1).min(points.len() - 1)] * acceleration,
+            curvature,
+            holonomic_rotation: heading,
+            angular_velocity: velocity * curvature,
+        });
+
+        if i + 1 < points.len() {
+            time += segment_time(velocities[i], velocities[i + 1], ds[i]);
+            distance += ds[i];
+        }
+    }
+
+    Ok(Trajectory { states })
+}
+
+// speed at every point, as fast as the limits allow; the robot comes to a
+// stop wherever the driving direction changes, so every stretch driven in one
+// direction gets its own rest-to-rest profile
+fn velocity_profile(
+    points: &[Point],
+    ds: &[f64],
+    directions: &[f64],
+    config: &TrajectoryConfig,
+    constraints: &[&dyn TrajectoryConstraint],
+) -> Vec<f64> {
+    let n = points.len();
+    let mut velocities = vec![0.; n];
+    if n == 0 {
+        return velocities;
+    }
+
+    // the step after point i is driven in the direction of point i + 1, so a
+    // change between them makes point i the cusp shared by both stretches
+    let mut cusps: Vec<usize> = (0..n - 1)
+        .filter(|&i| directions[i] != directions[i + 1])
+        .collect();
+    cusps.push(n - 1);
+
+    let mut start = 0;
+    for end in cusps {
+        let start_velocity = if start == 0 {
+            config.start_velocity
+        } else {
+            0.
+        };
+        let end_velocity = if end == n - 1 {
+            config.end_velocity
+        } else {
+            0.
+        };
+
+        let stretch = stretch_profile(
+            &points[start..=end],
+            &ds[start..end],
+            start_velocity,
+            end_velocity,
+            config,
+            constraints,
+        );
+        velocities[start..=end].copy_from_slice(&stretch);
+        start = end;
+    }
+
+    velocities
+}
+
+fn stretch_profile(
+    points: &[Point],
+    ds: &[f64],
+    start_velocity: f64,
+    end_velocity: f64,
+    config: &TrajectoryConfig,
+    constraints: &[&dyn TrajectoryConstraint],
+) -> Vec<f64> {
+    let mut velocities: Vec<f64> = points
+        .iter()
+        .map(|point| {
+            constraints
+                .iter()
+                .map(|constraint| constraint.max_velocity(point))
+                .fold(config.max_velocity, f64::min)
+        })
+        .collect();
+
+    let limits = |point: &Point, velocity: f64| {
+        constraints.iter().fold(
+            AccelerationLimits::new(-config.max_acceleration, config.max_acceleration),
+            |acc, constraint| acc.intersect(&constraint.acceleration_limits(point, velocity)),
+        )
+    };
+
+    if let Some(v) = velocities.first_mut() {
+        *v = v.min(start_velocity);
+    }
+
+    // forward pass: accelerate as hard as allowed from the start
+    for i in 1..velocities.len() {
+        let max_acceleration = limits(&points[i - 1], velocities[i - 1]).max;
+        let reachable = (velocities[i - 1].powi(2) + 2. * max_acceleration * ds[i - 1])
+            .max(0.)
+            .sqrt();
+        velocities[i] = velocities[i].min(reachable);
+    }
+
+    if let Some(v) = velocities.last_mut() {
+        *v = v.min(end_velocity);
+    }
+
+    // backward pass: make sure every state can still brake for the next one
+    for i in (0..velocities.len().saturating_sub(1)).rev() {
+        let min_acceleration = limits(&points[i + 1], velocities[i + 1]).min;
+        let reachable = (velocities[i + 1].powi(2) - 2. * min_acceleration * ds[i])
+            .max(0.)
+            .sqrt();
+        velocities[i] = velocities[i].min(reachable);
+    }
+
+    velocities
+}
+
+// the chord between two points is driven in the second point's direction
+fn midpoint(p0: &Point, p1: &Point) -> Point {
+    let mid = |a: Vec2, b: Vec2| a.add(b).scale(0.5);
+    Point {
+        position: mid(p0.position, p1.position),
+        velocity: mid(p0.velocity, p1.velocity),
+        acceleration: mid(p0.acceleration, p1.acceleration),
+        jerk: mid(p0.jerk, p1.jerk),
+        reversed: p1.reversed,
+    }
+}
+
+// exact under constant acceleration; a step with length is never at rest on
+// both ends once the profile has been refined
+fn segment_time(v0: f64, v1: f64, ds: f64) -> f64 {
+    if ds <= 0. {
+        0.
+    } else {
+        2. * ds / (v0 + v1)
+    }
+}
+
+fn interpolate(start: &State, end: &State, time: f64) -> State {
+    let dt = time - start.time;
+    let frac = dt / (end.time - start.time);
+
+    let velocity = start.velocity + start.acceleration * dt;
+    let distance = start.distance + ((start.velocity + velocity) / 2. * dt).abs();
+
+    let travelled = end.distance - start.distance;
+    let along = if travelled > 1e-9 {
+        (distance - start.distance) / travelled
+    } else {
+        frac
+    };
+
+    let rotation_delta =
+        Translation::rotate_by(end.holonomic_rotation, start.holonomic_rotation.inverse())
+            .as_radians();
+
+    State {
+        time,
+        distance,
+        pose: start.pose.interpolate(&end.pose, along),
+        velocity,
+        acceleration: start.acceleration,
+        curvature: start.curvature + (end.curvature - start.curvature) * frac,
+        holonomic_rotation: Translation::rotate_by(
+            start.holonomic_rotation,
+            Vec2::from_radians(rotation_delta * frac),
+        ),
+        angular_velocity: start.angular_velocity
+            + (end.angular_velocity - start.angular_velocity) * frac,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hermites, Directed, Jaci, Parameterizer, Waypoint};
+
+    const EPS: f64 = 1e-9;
+
+    fn s_curve() -> Vec<Point> {
+        let wps = [
+            Waypoint::new(0., 0., 1., 0., 0., 0.),
+            Waypoint::new(3., 1., 1., 0., 0., 0.),
+            Waypoint::new(6., 0., 1., -1., 0., 0.),
+        ];
+        Jaci::new(0.1, 0.01).parameterize(&hermites(&wps))
+    }
+
+    fn line(length: f64) -> Vec<Point> {
+        let point = |x: f64| Point {
+            position: [x, 0.],
+            velocity: [1., 0.],
+            acceleration: [0., 0.],
+            jerk: [0., 0.],
+            reversed: false,
+        };
+        vec![point(0.), point(length)]
+    }
+
+    // integral of |v| over time, exact for piecewise constant acceleration
+    fn travelled(trajectory: &Trajectory) -> f64 {
+        let states = trajectory.states();
+        states
+            .iter()
+            .zip(states.iter().skip(1))
+            .map(|(s0, s1)| (s0.velocity.abs() + s1.velocity.abs()) / 2. * (s1.time - s0.time))
+            .sum()
+    }
+
+    #[test]
+    fn respects_velocity_and_acceleration_limits() {
+        let config = TrajectoryConfig::new(2., 1., 0., 0.);
+        let trajectory = time_parameterize(&s_curve(), &config, &[]);
+
+        for state in trajectory.states() {
+            assert!(state.velocity.abs() <= 2. + EPS);
+            assert!(state.acceleration.abs() <= 1. + EPS);
+        }
+        assert!(trajectory.states().iter().any(|state| state.velocity > 1.9));
+    }
+
+    #[test]
+    fn starts_and_ends_at_the_given_velocities() {
+        let config = TrajectoryConfig::new(2., 1., 0.5, 1.5);
+        let trajectory = time_parameterize(&s_curve(), &config, &[]);
+        let states = trajectory.states();
+
+        assert!((states[0].velocity - 0.5).abs() < EPS);
+        assert!((states[states.len() - 1].velocity - 1.5).abs() < EPS);
+    }
+
+    #[test]
+    fn integrated_velocity_matches_distance() {
+        let config = TrajectoryConfig::new(2., 1., 0., 0.);
+        let trajectory = time_parameterize(&s_curve(), &config, &[]);
+        let last = trajectory.states().last().unwrap();
+
+        assert!((travelled(&trajectory) - last.distance).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cusp_stops_and_keeps_time_consistent_with_distance() {
+        let wps = [
+            Waypoint::new(0., 0., 1., 0., 0., 0.),
+            Waypoint::new(3., 1., 1., 0., 0., 0.),
+            Waypoint::new(0., 2., 1., 0., 0., 0.),
+        ];
+        let hs = hermites(&wps);
+        let segments = [Directed::forward(hs[0]), Directed::backward(hs[1])];
+        let points = Jaci::new(0.1, 0.01).parameterize(&segments);
+
+        let config = TrajectoryConfig::new(2., 1., 0., 0.);
+        let trajectory = time_parameterize(&points, &config, &[]);
+        let states = trajectory.states();
+        let last = states.last().unwrap();
+
+        assert!((travelled(&trajectory) - last.distance).abs() < 1e-6);
+        for (s0, s1) in states.iter().zip(states.iter().skip(1)) {
+            assert!(s1.time > s0.time);
+        }
+
+        // forward up to the cusp, at rest on it, backward after it
+        let cusp = states
+            .iter()
+            .position(|state| (state.pose.translation()[0] - 3.).abs() < 1e-9)
+            .unwrap();
+        assert_eq!(states[cusp].velocity, 0.);
+        assert!(states[1..cusp].iter().all(|state| state.velocity > 0.));
+        assert!(states[cusp + 1..states.len() - 1]
+            .iter()
+            .all(|state| state.velocity < 0.));
+    }
+
+    #[test]
+    fn rest_to_rest_step_gets_a_triangular_profile() {
+        let config = TrajectoryConfig::new(2., 1., 0., 0.);
+        let trajectory = time_parameterize(&line(1.), &config, &[]);
+
+        // accelerate for half the distance, then brake: 2 * sqrt(d / a)
+        assert!((trajectory.total_time() - 2.).abs() < EPS);
+        assert!((travelled(&trajectory) - 1.).abs() < EPS);
+    }
+
+    #[test]
+    fn unreachable_step_is_an_error() {
+        let config = TrajectoryConfig::new(0., 1., 0., 0.);
+        let result = try_time_parameterize(&line(1.), &config, &[]);
+
+        assert_eq!(
+            result.err(),
+            Some(WayfinderError::InfeasibleProfile { index: 0 })
+        );
+    }
+
+    #[test]
+    fn stationary_point_is_an_error() {
+        let mut points = line(1.);
+        points[1].velocity = [0., 0.];
+        let config = TrajectoryConfig::new(2., 1., 0., 0.);
+        let result = try_time_parameterize(&points, &config, &[]);
+
+        assert_eq!(
+            result.err(),
+            Some(WayfinderError::UndefinedHeading { index: 1 })
+        );
+    }
+
+    #[test]
+    fn sample_covers_the_trajectory_and_nothing_else() {
+        let config = TrajectoryConfig::new(2., 1., 0., 0.);
+        let trajectory = time_parameterize(&s_curve(), &config, &[]);
+        let states = trajectory.states();
+        let total = trajectory.total_time();
+
+        let start = trajectory.sample(0.).unwrap();
+        let end = trajectory.sample(total).unwrap();
+        assert_eq!(start.distance, states[0].distance);
+        assert_eq!(end.distance, states[states.len() - 1].distance);
+
+        let middle = trajectory.sample(total / 2.).unwrap();
+        assert!(middle.distance > 0. && middle.distance < end.distance);
+
+        assert!(trajectory.sample(-1e-3).is_none());
+        assert!(trajectory.sample(total + 1e-3).is_none());
+        assert!(Trajectory { states: Vec::new() }.sample(0.).is_none());
+    }
+}
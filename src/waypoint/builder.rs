@@ -0,0 +1,220 @@
+use crate::{check_waypoints, Coordinate, HermiteOptions, Vec2, Vector, WayfinderError, Waypoint};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct KochanekBartels {
+    pub tension: f64,
+    pub continuity: f64,
+    pub bias: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ControlPoint {
+    pub position: Vec2,
+    pub heading: Option<Vec2>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WaypointBuilder {
+    points: Vec<ControlPoint>,
+    tcb: KochanekBartels,
+    #[serde(default)]
+    hermite: HermiteOptions,
+}
+
+impl WaypointBuilder {
+    pub fn new() -> Self {
+        WaypointBuilder::default()
+    }
+
+    pub fn point(mut self, position: Vec2) -> Self {
+        self.points.push(ControlPoint {
+            position,
+            heading: None,
+        });
+        self
+    }
+
+    pub fn point_with_heading(mut self, position: Vec2, heading: Vec2) -> Self {
+        self.points.push(ControlPoint {
+            position,
+            heading: Some(heading),
+        });
+        self
+    }
+
+    pub fn points(mut self, points: &[ControlPoint]) -> Self {
+        self.points.extend_from_slice(points);
+        self
+    }
+
+    pub fn catmull_rom(mut self) -> Self {
+        self.tcb = KochanekBartels::default();
+        self
+    }
+
+    pub fn kochanek_bartels(mut self, tension: f64, continuity: f64, bias: f64) -> Self {
+        self.tcb = KochanekBartels {
+            tension,
+            continuity,
+            bias,
+        };
+        self
+    }
+
+    // the options the waypoints will be turned into hermites with, so that
+    // curvature is estimated from the tangents those segments actually use
+    pub fn hermite_options(mut self, hermite: HermiteOptions) -> Self {
+        self.hermite = hermite;
+        self
+    }
+
+    // fails on pinned headings without a direction, and on waypoints that
+    // try_hermites would reject
+    pub fn build(&self) -> Result<Vec<Waypoint>, WayfinderError> {
+        let n = self.points.len();
+        let position = |i: usize| self.points[i].position;
+
+        for (index, point) in self.points.iter().enumerate() {
+            if let Some(heading) = point.heading {
+                if !(heading.x().is_finite() && heading.y().is_finite()) {
+                    return Err(WayfinderError::NonFiniteWaypoint { index });
+                }
+                if heading.norm() == 0. {
+                    return Err(WayfinderError::ZeroTangent { index });
+                }
+            }
+        }
+
+        let tangents: Vec<Vec2> = (0..n)
+            .map(|i| {
+                if n < 2 {
+                    return [1., 0.];
+                }
+
+                // mirror the neighbouring chord at the ends of the path
+                let incoming = position(i.max(1)).add(position(i.max(1) - 1).scale(-1.));
+                let outgoing = position((i + 1).min(n - 1)).add(position(i.min(n - 2)).scale(-1.));
+                let tangent = self.tcb.tangent(incoming, outgoing, i + 1 == n);
+
+                match self.points[i].heading {
+                    Some(heading) => heading.unit().scale(tangent.norm()),
+                    None => tangent,
+                }
+            })
+            .collect();
+
+        let mut wps: Vec<Waypoint> = (0..n)
+            .map(|i| Waypoint {
+                point: position(i),
+                tangent: tangents[i],
+                curvature: [0., 0.],
+                holonomic_rotation: None,
+                tangent_scale: None,
+            })
+            .collect();
+
+        // second derivative from the change in tangent across each segment,
+        // with the tangents scaled the way the hermite will scale them
+        let changes: Vec<Vec2> = wps
+            .iter()
+            .zip(wps.iter().skip(1))
+            .map(|(start, end)| {
+                let (t0, t1) = self.hermite.tangent_scale.apply(start, end);
+                t1.add(t0.scale(-1.))
+            })
+            .collect();
+
+        for (i, wp) in wps.iter_mut().enumerate() {
+            wp.curvature = match i {
+                _ if n < 2 => [0., 0.],
+                0 => changes[0],
+                _ if i == n - 1 => changes[i - 1],
+                _ => changes[i - 1].add(changes[i]).scale(0.5),
+            };
+        }
+
+        check_waypoints(&wps)?;
+        Ok(wps)
+    }
+}
+
+impl KochanekBartels {
+    // A Waypoint only carries a single tangent, so continuity picks the
+    // outgoing (source) tangent everywhere except the last point, which only
+    // has an incoming (destination) one.
+    fn tangent(&self, incoming: Vec2, outgoing: Vec2, last: bool) -> Vec2 {
+        let (t, c, b) = (self.tension, self.continuity, self.bias);
+        let c = if last { c } else { -c };
+
+        incoming
+            .scale((1. - t) * (1. + b) * (1. + c) / 2.)
+            .add(outgoing.scale((1. - t) * (1. - b) * (1. - c) / 2.))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPS: f64 = 1e-12;
+
+    fn near(a: Vec2, b: Vec2) -> bool {
+        a.add(b.scale(-1.)).norm() < EPS
+    }
+
+    #[test]
+    fn kochanek_bartels_tangents() {
+        let wps = WaypointBuilder::new()
+            .point([0., 0.])
+            .point([1., 0.])
+            .point([1., 1.])
+            .point([3., 1.])
+            .kochanek_bartels(0.1, 0.5, 0.2)
+            .build()
+            .unwrap();
+
+        // outgoing tangent: (1 - t) / 2 * ((1 + b)(1 - c) in + (1 - b)(1 + c) out)
+        assert!(near(wps[0].tangent, [0.81, 0.]));
+        assert!(near(wps[1].tangent, [0.27, 0.54]));
+        assert!(near(wps[2].tangent, [1.08, 0.27]));
+        // incoming tangent: (1 - t) / 2 * ((1 + b)(1 + c) in + (1 - b)(1 - c) out)
+        assert!(near(wps[3].tangent, [1.98, 0.]));
+    }
+
+    #[test]
+    fn catmull_rom_averages_the_chords() {
+        let wps = WaypointBuilder::new()
+            .point([0., 0.])
+            .point([1., 0.])
+            .point([1., 1.])
+            .catmull_rom()
+            .build()
+            .unwrap();
+
+        assert!(near(wps[1].tangent, [0.5, 0.5]));
+    }
+
+    #[test]
+    fn pinned_heading_keeps_the_tangent_length() {
+        let wps = WaypointBuilder::new()
+            .point([0., 0.])
+            .point_with_heading([1., 0.], [0., 2.])
+            .point([1., 1.])
+            .build()
+            .unwrap();
+
+        assert!(near(wps[1].tangent, [0., 0.5f64.sqrt()]));
+    }
+
+    #[test]
+    fn zero_heading_is_rejected() {
+        let result = WaypointBuilder::new()
+            .point([0., 0.])
+            .point_with_heading([1., 0.], [0., 0.])
+            .point([1., 1.])
+            .build();
+
+        assert_eq!(result.err(), Some(WayfinderError::ZeroTangent { index: 1 }));
+    }
+}
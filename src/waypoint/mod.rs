@@ -1,4 +1,10 @@
-use crate::Vec2;
+mod builder;
+mod validate;
+
+pub use builder::*;
+pub use validate::*;
+
+use crate::{TangentScale, Vec2};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -6,6 +12,13 @@ pub struct Waypoint {
     pub point: Vec2,
     pub tangent: Vec2,
     pub curvature: Vec2,
+    // robot facing target for holonomic drivetrains, independent of tangent
+    #[serde(default)]
+    pub holonomic_rotation: Option<Vec2>,
+    // overrides HermiteOptions::tangent_scale for this waypoint's tangent, so
+    // saved waypoints keep their shape whatever options they are loaded with
+    #[serde(default)]
+    pub tangent_scale: Option<TangentScale>,
 }
 
 impl Waypoint {
@@ -14,6 +27,22 @@ impl Waypoint {
             point: [x, y],
             tangent: [dx, dy],
             curvature: [ddx, ddy],
+            holonomic_rotation: None,
+            tangent_scale: None,
+        }
+    }
+
+    pub fn with_holonomic_rotation(self, rotation: Vec2) -> Waypoint {
+        Waypoint {
+            holonomic_rotation: Some(rotation),
+            ..self
+        }
+    }
+
+    pub fn with_tangent_scale(self, tangent_scale: TangentScale) -> Waypoint {
+        Waypoint {
+            tangent_scale: Some(tangent_scale),
+            ..self
         }
     }
 }
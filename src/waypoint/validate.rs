@@ -0,0 +1,309 @@
+use crate::{
+    hermites_with, Coordinate, HermiteOptions, Rotation, Spline, Translation, Vec2, Vector,
+    Waypoint,
+};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValidationOptions {
+    pub max_curvature: f64,
+    pub max_heading_change: f64,
+    pub min_speed_ratio: f64,
+    pub samples: usize,
+    pub hermite: HermiteOptions,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        ValidationOptions {
+            max_curvature: f64::INFINITY,
+            max_heading_change: 3. * PI / 4.,
+            min_speed_ratio: 1e-2,
+            samples: 100,
+            hermite: HermiteOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum Warning {
+    NonFiniteWaypoint {
+        waypoint: usize,
+    },
+    ZeroTangent {
+        waypoint: usize,
+    },
+    CoincidentWaypoints {
+        segment: usize,
+    },
+    TangentOpposesSegment {
+        waypoint: usize,
+        segment: usize,
+    },
+    SharpHeadingChange {
+        segment: usize,
+        angle: f64,
+    },
+    Cusp {
+        segment: usize,
+        t: f64,
+    },
+    Loop {
+        segment: usize,
+    },
+    CurvatureExceeded {
+        segment: usize,
+        t: f64,
+        curvature: f64,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub warnings: Vec<Warning>,
+}
+
+impl ValidationReport {
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+pub fn validate(wps: &[Waypoint], opts: &ValidationOptions) -> ValidationReport {
+    // the geometric checks below are meaningless on degenerate input
+    let mut warnings = degenerate(wps);
+    if !warnings.is_empty() {
+        return ValidationReport { warnings };
+    }
+
+    for (segment, (start, end)) in wps.iter().zip(wps.iter().skip(1)).enumerate() {
+        let chord = end.point.add(start.point.scale(-1.));
+
+        if start.tangent.dot(&chord) < 0. {
+            warnings.push(Warning::TangentOpposesSegment {
+                waypoint: segment,
+                segment,
+            });
+        }
+        if end.tangent.dot(&chord) < 0. {
+            warnings.push(Warning::TangentOpposesSegment {
+                waypoint: segment + 1,
+                segment,
+            });
+        }
+
+        let angle = heading_change(start.tangent, end.tangent).abs();
+        if angle > opts.max_heading_change {
+            warnings.push(Warning::SharpHeadingChange { segment, angle });
+        }
+    }
+
+    for (segment, (hermite, start)) in hermites_with(wps, &opts.hermite)
+        .iter()
+        .zip(wps.iter())
+        .enumerate()
+    {
+        let chord = wps[segment + 1].point.add(start.point.scale(-1.)).norm();
+        let samples = opts.samples.max(1);
+        let ts: Vec<f64> = (0..=samples).map(|i| i as f64 / samples as f64).collect();
+
+        // velocity is measured per unit t, so compare it against the chord
+        if let Some(t) = ts
+            .iter()
+            .find(|t| hermite.velocity(**t).norm() < opts.min_speed_ratio * chord)
+        {
+            warnings.push(Warning::Cusp { segment, t: *t });
+        }
+
+        let turning: f64 = ts
+            .iter()
+            .zip(ts.iter().skip(1))
+            .map(|(t0, t1)| heading_change(hermite.velocity(*t0), hermite.velocity(*t1)).abs())
+            .sum();
+        if turning > 2. * PI {
+            warnings.push(Warning::Loop { segment });
+        }
+
+        let worst = ts
+            .iter()
+            .map(|t| (*t, hermite.curvature(*t)))
+            .filter(|(_, curvature)| curvature.is_finite())
+            .fold(
+                None,
+                |worst: Option<(f64, f64)>, (t, curvature)| match worst {
+                    Some((_, k)) if k.abs() >= curvature.abs() => worst,
+                    _ => Some((t, curvature)),
+                },
+            );
+        if let Some((t, curvature)) = worst {
+            if curvature.abs() > opts.max_curvature {
+                warnings.push(Warning::CurvatureExceeded {
+                    segment,
+                    t,
+                    curvature,
+                });
+            }
+        }
+    }
+
+    ValidationReport { warnings }
+}
+
+fn degenerate(wps: &[Waypoint]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+
+    for (waypoint, wp) in wps.iter().enumerate() {
+        let finite = [wp.point, wp.tangent, wp.curvature]
+            .iter()
+            .all(|v| v.x().is_finite() && v.y().is_finite());
+        if !finite {
+            warnings.push(Warning::NonFiniteWaypoint { waypoint });
+        } else if wp.tangent.norm() == 0. {
+            warnings.push(Warning::ZeroTangent { waypoint });
+        }
+    }
+
+    for (segment, (start, end)) in wps.iter().zip(wps.iter().skip(1)).enumerate() {
+        if end.point.add(start.point.scale(-1.)).norm() == 0. {
+            warnings.push(Warning::CoincidentWaypoints { segment });
+        }
+    }
+
+    warnings
+}
+
+fn heading_change(from: Vec2, to: Vec2) -> f64 {
+    Translation::rotate_by(to, from.inverse()).as_radians()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TangentScale;
+
+    fn warnings(wps: &[Waypoint], opts: &ValidationOptions) -> Vec<Warning> {
+        validate(wps, opts).warnings
+    }
+
+    fn raw() -> ValidationOptions {
+        ValidationOptions {
+            hermite: HermiteOptions {
+                tangent_scale: TangentScale::Raw,
+            },
+            ..ValidationOptions::default()
+        }
+    }
+
+    #[test]
+    fn gentle_path_is_clean() {
+        let wps = [
+            Waypoint::new(0., 0., 1., 0., 0., 0.),
+            Waypoint::new(4., 1., 1., 0., 0., 0.),
+            Waypoint::new(8., 0., 1., -0.5, 0., 0.),
+        ];
+        assert!(validate(&wps, &ValidationOptions::default()).is_clean());
+    }
+
+    #[test]
+    fn degenerate_waypoints_are_reported() {
+        let wps = [
+            Waypoint::new(0., 0., 1., 0., 0., 0.),
+            Waypoint::new(f64::NAN, 1., 1., 0., 0., 0.),
+            Waypoint::new(2., 0., 0., 0., 0., 0.),
+            Waypoint::new(2., 0., 1., 0., 0., 0.),
+        ];
+        let found = warnings(&wps, &ValidationOptions::default());
+
+        assert_eq!(
+            found,
+            vec![
+                Warning::NonFiniteWaypoint { waypoint: 1 },
+                Warning::ZeroTangent { waypoint: 2 },
+                Warning::CoincidentWaypoints { segment: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn tangent_against_the_segment() {
+        let wps = [
+            Waypoint::new(0., 0., 1., 0., 0., 0.),
+            Waypoint::new(4., 0., -1., 1., 0., 0.),
+        ];
+        let found = warnings(&wps, &ValidationOptions::default());
+
+        assert!(found.contains(&Warning::TangentOpposesSegment {
+            waypoint: 1,
+            segment: 0
+        }));
+    }
+
+    #[test]
+    fn sharp_heading_change() {
+        let wps = [
+            Waypoint::new(0., 0., 1., 0., 0., 0.),
+            Waypoint::new(0., 4., -1., 0., 0., 0.),
+        ];
+        let found = warnings(&wps, &ValidationOptions::default());
+
+        assert!(found.iter().any(|warning| matches!(
+            warning,
+            Warning::SharpHeadingChange { segment: 0, angle } if (angle - PI).abs() < 1e-9
+        )));
+    }
+
+    #[test]
+    fn near_zero_speed_is_a_cusp() {
+        let wps = [
+            Waypoint::new(0., 0., 1e-3, 0., 0., 0.),
+            Waypoint::new(1., 0., 1., 0., 0., 0.),
+        ];
+
+        assert!(warnings(&wps, &raw()).contains(&Warning::Cusp { segment: 0, t: 0. }));
+    }
+
+    #[test]
+    fn long_tangents_make_a_loop() {
+        let wps = [
+            Waypoint::new(0., 0., 20., 0., 0., 200.),
+            Waypoint::new(1., 0., 20., 0., 0., -200.),
+        ];
+
+        assert_eq!(warnings(&wps, &raw()), vec![Warning::Loop { segment: 0 }]);
+    }
+
+    #[test]
+    fn curvature_over_the_limit() {
+        let wps = [
+            Waypoint::new(0., 0., 1., 0., 0., 0.),
+            Waypoint::new(2., 2., 0., 1., 0., 0.),
+        ];
+        let opts = ValidationOptions {
+            max_curvature: 0.5,
+            ..ValidationOptions::default()
+        };
+        let found = warnings(&wps, &opts);
+
+        assert!(found.iter().any(|warning| matches!(
+            warning,
+            Warning::CurvatureExceeded { segment: 0, curvature, .. } if curvature.abs() > 0.5
+        )));
+        assert!(warnings(&wps, &ValidationOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn zero_samples_do_not_divide_by_zero() {
+        let wps = [
+            Waypoint::new(0., 0., 1., 0., 0., 0.),
+            Waypoint::new(4., 1., 1., 0., 0., 0.),
+        ];
+        let opts = ValidationOptions {
+            samples: 0,
+            ..ValidationOptions::default()
+        };
+        assert!(validate(&wps, &opts).is_clean());
+    }
+}